@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS boards (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO boards (id, name) VALUES (1, "Default");
+
+ALTER TABLE tasks ADD COLUMN board_id INTEGER NOT NULL DEFAULT 1 REFERENCES boards(id) ON DELETE CASCADE;
@@ -0,0 +1,9 @@
+ALTER TABLE tasks ADD COLUMN priority TEXT NOT NULL DEFAULT "none" CHECK (
+    priority IN (
+        "none",
+        "low",
+        "medium",
+        "high",
+        "urgent"
+    )
+);
@@ -0,0 +1,35 @@
+CREATE TABLE IF NOT EXISTS columns (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    board_id INTEGER NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    color TEXT NOT NULL DEFAULT "green",
+    status TEXT NOT NULL CHECK (
+        status IN (
+            "backlog",
+            "today",
+            "in_progress",
+            "done",
+            "archived"
+        )
+    )
+);
+
+-- Every existing board gets the five columns it used to have implicitly.
+INSERT INTO columns (board_id, name, position, status)
+SELECT boards.id, defaults.name, defaults.position, defaults.status
+FROM boards
+CROSS JOIN (
+    SELECT "BACKLOG" AS name, 0 AS position, "backlog" AS status
+    UNION ALL SELECT "TODAY", 1, "today"
+    UNION ALL SELECT "IN PROGRESS", 2, "in_progress"
+    UNION ALL SELECT "DONE", 3, "done"
+    UNION ALL SELECT "ARCHIVED", 4, "archived"
+) AS defaults;
+
+ALTER TABLE tasks ADD COLUMN column_id INTEGER REFERENCES columns(id);
+
+UPDATE tasks SET column_id = (
+    SELECT columns.id FROM columns
+    WHERE columns.board_id = tasks.board_id AND columns.status = tasks.status
+);
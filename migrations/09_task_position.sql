@@ -0,0 +1,4 @@
+ALTER TABLE tasks ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+-- Keeps the insertion order cards were shown in so far.
+UPDATE tasks SET position = id;
@@ -0,0 +1,15 @@
+-- No foreign key on task_id: the history of a task outlives the task.
+CREATE TABLE IF NOT EXISTS task_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    from_value TEXT,
+    to_value TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS task_events_task_id ON task_events (task_id);
+
+-- Existing tasks get their creation as the first event.
+INSERT INTO task_events (task_id, kind, to_value, created_at)
+SELECT id, 'created', description, created_at FROM tasks;
@@ -0,0 +1,4 @@
+-- Deleted tasks stay in the table, hidden from the board, until purged.
+ALTER TABLE tasks ADD COLUMN deleted_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS tasks_deleted_at ON tasks (deleted_at);
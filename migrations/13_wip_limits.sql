@@ -0,0 +1,2 @@
+-- Maximum number of cards in a column; NULL means no limit.
+ALTER TABLE columns ADD COLUMN wip_limit INTEGER CHECK (wip_limit > 0);
@@ -0,0 +1,7 @@
+-- What a synced file looked like when rstd last wrote it, so the next sync can
+-- tell which side changed a task.
+CREATE TABLE IF NOT EXISTS sync_state (
+    path TEXT PRIMARY KEY,
+    synced_at TIMESTAMP NOT NULL,
+    contents TEXT NOT NULL
+);
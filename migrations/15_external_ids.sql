@@ -0,0 +1,10 @@
+-- Ids other apps gave to tasks imported from them, such as iCalendar UIDs, so
+-- importing the same file again updates those tasks instead of copying them.
+CREATE TABLE IF NOT EXISTS external_ids (
+    source TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    PRIMARY KEY (source, external_id)
+);
+
+CREATE INDEX IF NOT EXISTS external_ids_task_id ON external_ids (task_id);
@@ -0,0 +1,4 @@
+-- Cards the user reordered by hand keep their position; the others sort by
+-- deadline and fall back to `position` only for equal deadlines.
+ALTER TABLE tasks ADD COLUMN ordered_manually INTEGER NOT NULL DEFAULT 0
+    CHECK (ordered_manually IN (0, 1));
@@ -0,0 +1,16 @@
+-- Random UUID of this database. Exported files carry it in their task ids, so
+-- an import only takes a task for one of ours when the file came from here.
+CREATE TABLE IF NOT EXISTS database_info (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    uuid TEXT NOT NULL
+);
+
+INSERT OR IGNORE INTO database_info (id, uuid) VALUES (
+    1,
+    lower(
+        hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' ||
+        substr(hex(randomblob(2)), 2) || '-' ||
+        substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-' ||
+        hex(randomblob(6))
+    )
+);
@@ -0,0 +1,110 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Local};
+use strum::EnumMessage;
+use strum_macros::{Display, EnumString};
+
+use crate::{task::Status, utils::db_timestamp_to_local_dt};
+
+#[derive(Display, EnumString, EnumMessage, Debug, Clone, Copy, PartialEq)]
+pub enum EventKind {
+    #[strum(serialize = "created", message = "Created")]
+    Created,
+    #[strum(serialize = "status_changed", message = "Status")]
+    StatusChanged,
+    #[strum(serialize = "column_changed", message = "Column")]
+    ColumnChanged,
+    #[strum(serialize = "description_changed", message = "Description")]
+    DescriptionChanged,
+    #[strum(serialize = "deadline_changed", message = "Deadline")]
+    DeadlineChanged,
+    #[strum(serialize = "priority_changed", message = "Priority")]
+    PriorityChanged,
+    #[strum(serialize = "tags_changed", message = "Tags")]
+    TagsChanged,
+    #[strum(serialize = "recurrence_changed", message = "Repeat")]
+    RecurrenceChanged,
+    #[strum(serialize = "board_changed", message = "Board")]
+    BoardChanged,
+    #[strum(serialize = "reordered", message = "Position")]
+    Reordered,
+    #[strum(serialize = "checklist_changed", message = "Checklist")]
+    ChecklistChanged,
+    #[strum(serialize = "blocker_added", message = "Blocked by")]
+    BlockerAdded,
+    #[strum(serialize = "blocker_removed", message = "Unblocked from")]
+    BlockerRemoved,
+    #[strum(serialize = "timer_started", message = "Timer started")]
+    TimerStarted,
+    #[strum(serialize = "timer_stopped", message = "Timer stopped")]
+    TimerStopped,
+    #[strum(serialize = "time_entry_changed", message = "Time entry")]
+    TimeEntryChanged,
+    #[strum(serialize = "time_entry_deleted", message = "Time entry deleted")]
+    TimeEntryDeleted,
+    #[strum(serialize = "updated_at_changed", message = "Modified")]
+    UpdatedAtChanged,
+    #[strum(serialize = "deleted", message = "Deleted")]
+    Deleted,
+    #[strum(serialize = "restored", message = "Restored")]
+    Restored,
+    #[strum(serialize = "purged", message = "Purged")]
+    Purged,
+}
+
+/// One entry of a task's activity log. `from` and `to` hold the old and new
+/// value in their stored form, e.g. `in_progress` or an RFC 3339 deadline.
+#[derive(Debug, Clone)]
+pub struct TaskEvent {
+    pub kind: EventKind,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub created_at: DateTime<Local>,
+}
+
+impl TaskEvent {
+    pub fn title(&self) -> &'static str {
+        self.kind.get_message().unwrap()
+    }
+
+    /// Human readable summary of the change, e.g. `To Do → In Progress`.
+    pub fn summary(&self) -> String {
+        let from = self.from.as_deref().map(|v| self.display_value(v));
+        let to = self.to.as_deref().map(|v| self.display_value(v));
+        match (from, to) {
+            (Some(from), Some(to)) => format!("{from} → {to}"),
+            (None, Some(to)) => format!("→ {to}"),
+            (Some(from), None) => format!("{from} →"),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn display_value(&self, value: &str) -> String {
+        match self.kind {
+            EventKind::StatusChanged => Status::from_str(value)
+                .ok()
+                .and_then(|s| s.get_message())
+                .unwrap_or(value)
+                .to_string(),
+            EventKind::DeadlineChanged
+            | EventKind::TimerStarted
+            | EventKind::TimerStopped
+            | EventKind::UpdatedAtChanged => db_timestamp_to_local_dt(value)
+                .format("%d/%m/%Y %H:%M")
+                .to_string(),
+            EventKind::TimeEntryChanged | EventKind::TimeEntryDeleted => {
+                let (start, end) = value.split_once('/').unwrap_or((value, ""));
+                let start = db_timestamp_to_local_dt(start);
+                match end {
+                    "" => format!("{}–", start.format("%d/%m/%Y %H:%M")),
+                    end => format!(
+                        "{}–{}",
+                        start.format("%d/%m/%Y %H:%M"),
+                        db_timestamp_to_local_dt(end).format("%H:%M")
+                    ),
+                }
+            }
+            _ => value.lines().next().unwrap_or("").to_string(),
+        }
+    }
+}
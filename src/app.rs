@@ -1,7 +1,21 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime};
+
 use crate::{
+    activity::TaskEvent,
+    board::Board,
+    checklist::ChecklistItem,
+    column::Column,
+    config::{Config, TodayRollover},
     db::Database,
     error::AppError,
-    task::{Status, Task},
+    recurrence::Recurrence,
+    task::{Priority, Status, Task},
+    text_input::TextInput,
+    time_entry::{TimeEntry, TimeSummary},
+    undo::{Command, UndoStack},
+    utils::str_to_local_dt,
 };
 
 pub struct AppControl {
@@ -9,7 +23,7 @@ pub struct AppControl {
     pub title: &'static str,
 }
 
-pub const BOARD_CONTROLS: [AppControl; 6] = [
+pub const BOARD_CONTROLS: [AppControl; 19] = [
     AppControl {
         key_binding: "q",
         title: "Quit",
@@ -26,6 +40,10 @@ pub const BOARD_CONTROLS: [AppControl; 6] = [
         key_binding: "↑↓",
         title: "Select task",
     },
+    AppControl {
+        key_binding: "Shift + ↑↓",
+        title: "Reorder task",
+    },
     AppControl {
         key_binding: "d",
         title: "Delete task",
@@ -34,16 +52,304 @@ pub const BOARD_CONTROLS: [AppControl; 6] = [
         key_binding: "n",
         title: "Create task",
     },
+    AppControl {
+        key_binding: "e",
+        title: "Edit task",
+    },
+    AppControl {
+        key_binding: "b",
+        title: "Boards",
+    },
+    AppControl {
+        key_binding: "t",
+        title: "Filter by tag",
+    },
+    AppControl {
+        key_binding: "p",
+        title: "Change priority",
+    },
+    AppControl {
+        key_binding: "c",
+        title: "Checklist",
+    },
+    AppControl {
+        key_binding: "l",
+        title: "Blocked by",
+    },
+    AppControl {
+        key_binding: "w",
+        title: "Columns",
+    },
+    AppControl {
+        key_binding: "h",
+        title: "History",
+    },
+    AppControl {
+        key_binding: "s",
+        title: "Start/stop timer",
+    },
+    AppControl {
+        key_binding: "i",
+        title: "Time entries",
+    },
+    AppControl {
+        key_binding: "r",
+        title: "Time report",
+    },
+    AppControl {
+        key_binding: "x",
+        title: "Trash",
+    },
+];
+
+pub const TRASH_CONTROLS: [AppControl; 4] = [
+    AppControl {
+        key_binding: "↑↓",
+        title: "Select task",
+    },
+    AppControl {
+        key_binding: "Enter",
+        title: "Restore task",
+    },
+    AppControl {
+        key_binding: "d",
+        title: "Delete forever",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const TIME_ENTRIES_CONTROLS: [AppControl; 5] = [
+    AppControl {
+        key_binding: "↑↓",
+        title: "Select entry",
+    },
+    AppControl {
+        key_binding: "+/-",
+        title: "Adjust by 5 min",
+    },
+    AppControl {
+        key_binding: "s",
+        title: "Start/stop timer",
+    },
+    AppControl {
+        key_binding: "d",
+        title: "Delete entry",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const TIME_REPORT_CONTROLS: [AppControl; 2] = [
+    AppControl {
+        key_binding: "↑↓",
+        title: "Scroll",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const HISTORY_CONTROLS: [AppControl; 2] = [
+    AppControl {
+        key_binding: "↑↓",
+        title: "Scroll",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const COLUMNS_CONTROLS: [AppControl; 9] = [
+    AppControl {
+        key_binding: "↑↓",
+        title: "Select column",
+    },
+    AppControl {
+        key_binding: "←→",
+        title: "Reorder column",
+    },
+    AppControl {
+        key_binding: "n",
+        title: "Create column",
+    },
+    AppControl {
+        key_binding: "r",
+        title: "Rename column",
+    },
+    AppControl {
+        key_binding: "s",
+        title: "Change stage",
+    },
+    AppControl {
+        key_binding: "k",
+        title: "Change color",
+    },
+    AppControl {
+        key_binding: "+/-",
+        title: "Change WIP limit",
+    },
+    AppControl {
+        key_binding: "d",
+        title: "Delete column",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const COLUMN_MODAL_CONTROLS: [AppControl; 2] = [
+    AppControl {
+        key_binding: "Enter",
+        title: "Save column",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const DEPENDENCIES_CONTROLS: [AppControl; 3] = [
+    AppControl {
+        key_binding: "↑↓",
+        title: "Select task",
+    },
+    AppControl {
+        key_binding: "Space",
+        title: "Toggle blocker",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const CHECKLIST_CONTROLS: [AppControl; 6] = [
+    AppControl {
+        key_binding: "↑↓",
+        title: "Select item",
+    },
+    AppControl {
+        key_binding: "Space",
+        title: "Toggle done",
+    },
+    AppControl {
+        key_binding: "n",
+        title: "Add item",
+    },
+    AppControl {
+        key_binding: "e",
+        title: "Edit item",
+    },
+    AppControl {
+        key_binding: "d",
+        title: "Delete item",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const CHECKLIST_MODAL_CONTROLS: [AppControl; 2] = [
+    AppControl {
+        key_binding: "Enter",
+        title: "Save item",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const BOARDS_CONTROLS: [AppControl; 7] = [
+    AppControl {
+        key_binding: "↑↓",
+        title: "Select board",
+    },
+    AppControl {
+        key_binding: "Enter",
+        title: "Switch to board",
+    },
+    AppControl {
+        key_binding: "n",
+        title: "Create board",
+    },
+    AppControl {
+        key_binding: "r",
+        title: "Rename board",
+    },
+    AppControl {
+        key_binding: "d",
+        title: "Delete board",
+    },
+    AppControl {
+        key_binding: "m",
+        title: "Move selected task here",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const BOARD_MODAL_CONTROLS: [AppControl; 2] = [
+    AppControl {
+        key_binding: "Enter",
+        title: "Save board",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
 ];
 
-pub const TASK_MODAL_CONTROLS: [AppControl; 3] = [
+pub const TASK_MODAL_CONTROLS: [AppControl; 5] = [
     AppControl {
         key_binding: "Ctrl + s",
         title: "Create task",
     },
+    AppControl {
+        key_binding: "Ctrl + p",
+        title: "Change priority",
+    },
+    AppControl {
+        key_binding: "Ctrl + d",
+        title: "Pick date",
+    },
+    AppControl {
+        key_binding: "Tab",
+        title: "Switch field",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Go back",
+    },
+];
+
+pub const EDIT_TASK_MODAL_CONTROLS: [AppControl; 5] = [
+    AppControl {
+        key_binding: "Ctrl + s",
+        title: "Edit task",
+    },
+    AppControl {
+        key_binding: "Ctrl + p",
+        title: "Change priority",
+    },
+    AppControl {
+        key_binding: "Ctrl + d",
+        title: "Pick date",
+    },
     AppControl {
         key_binding: "Tab",
-        title: "Description/deadline switch",
+        title: "Switch field",
     },
     AppControl {
         key_binding: "Esc",
@@ -51,14 +357,83 @@ pub const TASK_MODAL_CONTROLS: [AppControl; 3] = [
     },
 ];
 
+pub const CALENDAR_CONTROLS: [AppControl; 4] = [
+    AppControl {
+        key_binding: "←→↑↓",
+        title: "Move day",
+    },
+    AppControl {
+        key_binding: "PgUp/PgDn",
+        title: "Change month",
+    },
+    AppControl {
+        key_binding: "Enter",
+        title: "Pick date",
+    },
+    AppControl {
+        key_binding: "Esc",
+        title: "Close calendar",
+    },
+];
+
 pub enum AppMode {
     Board,
     NewTask,
+    EditTask,
+    Boards,
+    NewBoard,
+    EditBoard,
+    Checklist,
+    NewChecklistItem,
+    EditChecklistItem,
+    Dependencies,
+    History,
+    TimeEntries,
+    TimeReport,
+    Trash,
+    Columns,
+    NewColumn,
+    EditColumn,
 }
 
 pub struct AppState {
+    pub boards: Vec<Board>,
+    pub current_board: i64,
+    pub selected_board_index: usize,
+    pub columns: Vec<Column>,
     pub tasks: Vec<Task>,
-    pub selected_status: Status,
+    pub tag_filter: Option<String>,
+    /// Task whose checklist is open in the checklist editor.
+    pub checklist_task: Option<Task>,
+    pub checklist: Vec<ChecklistItem>,
+    pub selected_checklist_index: usize,
+    /// Task whose blockers are open in the dependencies editor.
+    pub dependencies_task: Option<Task>,
+    pub dependencies: Vec<i64>,
+    pub selected_dependency_index: usize,
+    /// Task whose activity log is open in the history panel.
+    pub history_task: Option<Task>,
+    pub history: Vec<TaskEvent>,
+    pub history_scroll: u16,
+    /// Task whose time entries are open in the time entries editor.
+    pub time_task: Option<Task>,
+    pub time_entries: Vec<TimeEntry>,
+    pub selected_time_entry_index: usize,
+    /// Tracked time of the current board per day and task, newest day first.
+    pub time_report: Vec<TimeSummary>,
+    pub time_report_scroll: u16,
+    /// Deleted tasks of the current board shown in the trash view.
+    pub trash: Vec<Task>,
+    pub selected_trash_index: usize,
+    /// User settings from `.rstd/config`.
+    pub config: Config,
+    /// Day the rollover and archiving policies last ran.
+    pub policies_run_on: Option<NaiveDate>,
+    /// Task changes of this session that `u` and Ctrl+R step through.
+    pub undo_stack: UndoStack,
+    /// One-off message shown in the header until the next key press.
+    pub status_message: Option<String>,
+    pub selected_column: usize,
     pub selected_index: usize,
     pub should_quit: bool,
     pub mode: AppMode,
@@ -66,105 +441,1233 @@ pub struct AppState {
 
 impl AppState {
     pub fn new(db: &Database) -> AppState {
-        let tasks: Vec<Task> = db.load_tasks().expect("Failed to load tasks");
+        let boards: Vec<Board> = db.load_boards().expect("Failed to load boards");
+        let current_board = boards.first().and_then(|b| b.id).unwrap_or(1);
+        let columns: Vec<Column> = db
+            .load_columns(current_board)
+            .expect("Failed to load columns");
+        let tasks: Vec<Task> = db.load_tasks(current_board).expect("Failed to load tasks");
 
         AppState {
-            tasks: tasks,
-            selected_status: Status::Backlog,
+            boards,
+            current_board,
+            selected_board_index: 0,
+            columns,
+            tasks,
+            tag_filter: None,
+            checklist_task: None,
+            checklist: Vec::new(),
+            selected_checklist_index: 0,
+            dependencies_task: None,
+            dependencies: Vec::new(),
+            selected_dependency_index: 0,
+            history_task: None,
+            history: Vec::new(),
+            history_scroll: 0,
+            time_task: None,
+            time_entries: Vec::new(),
+            selected_time_entry_index: 0,
+            time_report: Vec::new(),
+            time_report_scroll: 0,
+            trash: Vec::new(),
+            selected_trash_index: 0,
+            config: Config::default(),
+            policies_run_on: None,
+            undo_stack: UndoStack::default(),
+            status_message: None,
+            selected_column: 0,
             selected_index: 0,
             should_quit: false,
             mode: AppMode::Board,
         }
     }
 
-    pub fn tasks_for_status(&self, status: &Status) -> Vec<&Task> {
-        self.tasks.iter().filter(|t| &t.status == status).collect()
+    pub fn current_column(&self) -> Option<&Column> {
+        self.columns.get(self.selected_column)
+    }
+
+    /// Tasks of a column in the order of `card_order`.
+    pub fn tasks_for_column(&self, column: &Column) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|t| t.column_id == column.id)
+            .filter(|t| match &self.tag_filter {
+                Some(tag) => t.tags.contains(tag),
+                None => true,
+            })
+            .collect();
+        tasks.sort_by(|a, b| card_order(a, b));
+        tasks
+    }
+
+    /// Number of cards in a column, regardless of the tag filter.
+    pub fn column_task_count(&self, column: &Column) -> usize {
+        self.tasks
+            .iter()
+            .filter(|t| t.column_id == column.id)
+            .count()
+    }
+
+    fn selected_column_tasks(&self) -> Vec<&Task> {
+        self.current_column()
+            .map(|c| self.tasks_for_column(c))
+            .unwrap_or_default()
+    }
+
+    fn select_task(&mut self, id: Option<i64>) {
+        if let Some(idx) = self.selected_column_tasks().iter().position(|t| t.id == id) {
+            self.selected_index = idx;
+        }
+    }
+
+    /// Cycles the board filter through no filter and every tag in use.
+    pub fn cycle_tag_filter(&mut self, db: &Database) -> Result<(), AppError> {
+        let tags = db.load_tags(self.current_board)?;
+        self.tag_filter = match &self.tag_filter {
+            None => tags.first().cloned(),
+            Some(current) => tags
+                .iter()
+                .position(|t| t == current)
+                .and_then(|i| tags.get(i + 1))
+                .cloned(),
+        };
+        self.selected_index = 0;
+        Ok(())
+    }
+
+    pub fn selected_task(&self) -> Option<&Task> {
+        self.selected_column_tasks()
+            .get(self.selected_index)
+            .copied()
+    }
+
+    pub fn next_column_index(&self) -> usize {
+        (self.selected_column + 1) % self.columns.len().max(1)
+    }
+
+    pub fn prev_column_index(&self) -> usize {
+        let len = self.columns.len().max(1);
+        (self.selected_column + len - 1) % len
     }
 
-    pub fn select_next_status(&mut self) {
-        self.selected_status = self.selected_status.next();
+    pub fn select_next_column(&mut self) {
+        self.selected_column = self.next_column_index();
         self.selected_index = 0;
     }
 
     pub fn select_next_task(&mut self) {
-        let tasks_len = self.tasks_for_status(&self.selected_status).len();
+        let tasks_len = self.selected_column_tasks().len();
         if self.selected_index >= tasks_len {
             return;
         }
-        self.selected_index =
-            (self.selected_index + 1) % self.tasks_for_status(&self.selected_status).len();
+        self.selected_index = (self.selected_index + 1) % tasks_len;
     }
 
     pub fn select_prev_task(&mut self) {
         if self.selected_index == 0 {
             return;
         }
-        self.selected_index =
-            (self.selected_index - 1) % self.tasks_for_status(&self.selected_status).len();
+        self.selected_index = (self.selected_index - 1) % self.selected_column_tasks().len();
     }
 
     pub fn move_task_to_column(
         &mut self,
         db: &mut Database,
-        status: &Status,
+        column_index: usize,
     ) -> Result<(), AppError> {
-        let tasks = self.tasks_for_status(&self.selected_status);
-        if tasks.is_empty() {
+        let (Some(task), Some(column)) = (
+            self.selected_task().cloned(),
+            self.columns.get(column_index).cloned(),
+        ) else {
+            return Ok(());
+        };
+        let moved = Command::move_task(db, &task, &column, self.config.wip_limits)?;
+        let next = moved.next_occurrence().map(|next| {
+            format!(
+                "Next occurrence scheduled for {}",
+                next.deadline
+                    .map(|d| d.format("%d/%m/%Y %H:%M").to_string())
+                    .unwrap_or_default()
+            )
+        });
+        self.undo_stack.execute(db, moved.command)?;
+        self.status_message = moved.wip_warning.or(next);
+        self.reload_tasks(db);
+        self.selected_column = column_index;
+        self.select_task(task.id);
+        Ok(())
+    }
+
+    /// Moves the selected task up (-1) or down (1) inside its column. Cards
+    /// stay grouped by priority, so they cannot pass a card of another one.
+    pub fn shift_task(&mut self, db: &mut Database, offset: isize) -> Result<(), AppError> {
+        let tasks = self.selected_column_tasks();
+        let (Some(task), Some(other)) = (
+            tasks.get(self.selected_index).copied().cloned(),
+            self.selected_index
+                .checked_add_signed(offset)
+                .and_then(|i| tasks.get(i))
+                .copied()
+                .cloned(),
+        ) else {
+            return Ok(());
+        };
+        if task.priority != other.priority {
+            self.status_message =
+                Some("Cards are grouped by priority; press p to change it".to_string());
             return Ok(());
         }
-        let mut task = tasks[self.selected_index].clone();
-        task.status = *status;
-        db.update_task(&task).expect("Failed to update the task");
-        self.tasks = db.load_tasks().expect("Failed to update tasks list");
-        self.selected_status = *status;
-        if let Some(idx) = self
-            .tasks_for_status(status)
+
+        // The cards of the priority group down to the moved ones are pinned
+        // in the order they are shown, tag filter aside, before the two swap.
+        // Manually ordered cards come first in their group, so the ones
+        // below keep sorting by deadline.
+        let mut group: Vec<&Task> = self
+            .tasks
             .iter()
-            .position(|t| t.id == task.id)
+            .filter(|t| t.column_id == task.column_id && t.priority == task.priority)
+            .collect();
+        group.sort_by(|a, b| card_order(a, b));
+        let index = |id| group.iter().position(|t| t.id == id).unwrap_or_default();
+        let (task_index, other_index) = (index(task.id), index(other.id));
+        let mut commands = Vec::new();
+        for (i, t) in group
+            .iter()
+            .enumerate()
+            .take(task_index.max(other_index) + 1)
         {
-            self.selected_index = idx;
+            let position = if i == task_index {
+                other_index
+            } else if i == other_index {
+                task_index
+            } else {
+                i
+            } as i64;
+            if t.ordered_manually && t.position == Some(position) {
+                continue;
+            }
+            let command = Command::update(
+                (*t).clone(),
+                Task {
+                    position: Some(position),
+                    ordered_manually: true,
+                    ..(*t).clone()
+                },
+            );
+            // The moved card goes first, so undo reselects it.
+            if i == task_index {
+                commands.insert(0, command);
+            } else {
+                commands.push(command);
+            }
         }
+        self.undo_stack.execute(db, Command::Batch(commands))?;
+        self.reload_tasks(db);
+        self.select_task(task.id);
+        Ok(())
+    }
+
+    pub fn cycle_priority(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let Some(before) = self.selected_task().cloned() else {
+            return Ok(());
+        };
+        // The card sorts by deadline again in its new priority group.
+        let task = Task {
+            priority: before.priority.next(),
+            ordered_manually: false,
+            ..before.clone()
+        };
+        self.undo_stack
+            .execute(db, Command::update(before, task.clone()))?;
+        self.reload_tasks(db);
+        self.select_task(task.id);
         Ok(())
     }
 
     pub fn create_task(&mut self, db: &mut Database, task: &Task) {
-        db.insert_task(task).expect("failed to create a task");
-        self.tasks = db.load_tasks().expect("Failed to update tasks list");
+        self.undo_stack
+            .execute(
+                db,
+                Command::Create {
+                    task: task.clone(),
+                    checklist: Vec::new(),
+                },
+            )
+            .expect("failed to create a task");
+        self.reload_tasks(db);
+    }
+
+    pub fn update_task(&mut self, db: &mut Database, task: &Task) {
+        let Some(before) = self.tasks.iter().find(|t| t.id == task.id).cloned() else {
+            return;
+        };
+        self.undo_stack
+            .execute(db, Command::update(before, task.clone()))
+            .expect("failed to update a task");
+        self.reload_tasks(db);
     }
 
     pub fn delete_task(&mut self, db: &mut Database) {
-        let task = self.tasks_for_status(&self.selected_status)[self.selected_index];
-        db.delete_task(task).expect("failed to delete task");
-        self.tasks = db.load_tasks().expect("Failed to update tasks list");
+        let Some(task) = self.selected_task().cloned() else {
+            return;
+        };
+        self.undo_stack
+            .execute(db, Command::Delete(task))
+            .expect("failed to delete task");
+        self.reload_tasks(db);
         self.selected_index = 0;
     }
 
-    pub fn switch_mode(&mut self) {
-        match self.mode {
-            AppMode::Board => self.mode = AppMode::NewTask,
-            AppMode::NewTask => self.mode = AppMode::Board,
-        }
+    pub fn undo(&mut self, db: &mut Database) {
+        let result = self
+            .undo_stack
+            .undo(db)
+            .map(|c| c.map(|c| (c.title(), c.task_id())));
+        self.after_undo_or_redo(db, "Undid", "undo", result);
     }
-}
 
-pub struct TaskModalState {
-    pub description_in: String,
-    pub deadline_in: String,
-    pub entering_deadline: bool,
-}
+    pub fn redo(&mut self, db: &mut Database) {
+        let result = self
+            .undo_stack
+            .redo(db)
+            .map(|c| c.map(|c| (c.title(), c.task_id())));
+        self.after_undo_or_redo(db, "Redid", "redo", result);
+    }
+
+    /// Reports the outcome in the status message and selects the task the
+    /// command touched.
+    fn after_undo_or_redo(
+        &mut self,
+        db: &Database,
+        done: &str,
+        action: &str,
+        result: Result<Option<(&str, Option<i64>)>, AppError>,
+    ) {
+        let task_id = match result {
+            Ok(Some((title, task_id))) => {
+                self.status_message = Some(format!("{done} {title}"));
+                task_id
+            }
+            Ok(None) => {
+                self.status_message = Some(format!("Nothing to {action}"));
+                None
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Cannot {action}: {e}"));
+                None
+            }
+        };
+        self.reload_tasks(db);
+        let column_id = self
+            .tasks
+            .iter()
+            .find(|t| task_id.is_some() && t.id == task_id)
+            .and_then(|t| t.column_id);
+        if let Some(index) = self
+            .columns
+            .iter()
+            .position(|c| c.id == column_id && c.id.is_some())
+        {
+            self.selected_column = index;
+            self.select_task(task_id);
+        } else if self.selected_index >= self.selected_column_tasks().len() {
+            self.selected_index = 0;
+        }
+    }
+
+    pub fn reload_tasks(&mut self, db: &Database) {
+        self.tasks = db
+            .load_tasks(self.current_board)
+            .expect("Failed to update tasks list");
+    }
+
+    pub fn reload_columns(&mut self, db: &Database) {
+        self.columns = db
+            .load_columns(self.current_board)
+            .expect("Failed to update columns list");
+        if self.selected_column >= self.columns.len() {
+            self.selected_column = self.columns.len().saturating_sub(1);
+        }
+    }
+
+    pub fn create_column(&mut self, db: &mut Database, name: String) -> Result<(), AppError> {
+        let position = self.columns.last().map(|c| c.position + 1).unwrap_or(0);
+        let column = Column::new(self.current_board, name, position, Status::Backlog);
+        db.insert_column(&column)?;
+        self.reload_columns(db);
+        self.selected_column = self.columns.len().saturating_sub(1);
+        Ok(())
+    }
+
+    pub fn update_column(&mut self, db: &mut Database, column: &Column) -> Result<(), AppError> {
+        db.update_column(column)?;
+        self.reload_columns(db);
+        self.reload_tasks(db);
+        Ok(())
+    }
+
+    pub fn cycle_column_status(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let Some(mut column) = self.current_column().cloned() else {
+            return Ok(());
+        };
+        column.status = column.status.next();
+        self.update_column(db, &column)
+    }
+
+    pub fn cycle_column_color(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let Some(mut column) = self.current_column().cloned() else {
+            return Ok(());
+        };
+        column.color = column.next_color();
+        self.update_column(db, &column)
+    }
+
+    /// Raises (1) or lowers (-1) the WIP limit of the selected column; lowering
+    /// it below one removes the limit.
+    pub fn change_column_wip_limit(
+        &mut self,
+        db: &mut Database,
+        delta: i32,
+    ) -> Result<(), AppError> {
+        let Some(mut column) = self.current_column().cloned() else {
+            return Ok(());
+        };
+        let limit = column.wip_limit.unwrap_or_default() as i32 + delta;
+        column.wip_limit = (limit > 0).then_some(limit as u32);
+        self.update_column(db, &column)
+    }
+
+    /// Swaps the selected column with its neighbour; `offset` is -1 or 1.
+    pub fn shift_column(&mut self, db: &mut Database, offset: isize) -> Result<(), AppError> {
+        let Some(other_index) = self
+            .selected_column
+            .checked_add_signed(offset)
+            .filter(|i| *i < self.columns.len())
+        else {
+            return Ok(());
+        };
+        let mut columns = self.columns.clone();
+        columns.swap(self.selected_column, other_index);
+        // Positions are renumbered so columns that shared a position still swap.
+        for (position, column) in columns.iter_mut().enumerate() {
+            column.position = position as i64;
+            db.update_column(column)?;
+        }
+        self.selected_column = other_index;
+        self.reload_columns(db);
+        Ok(())
+    }
+
+    /// Deletes the selected column if it is empty and not the last one.
+    pub fn delete_selected_column(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let Some(column) = self.current_column().cloned() else {
+            return Ok(());
+        };
+        if self.columns.len() <= 1 {
+            self.status_message = Some("A board needs at least one column".to_string());
+            return Ok(());
+        }
+        if self.tasks.iter().any(|t| t.column_id == column.id) {
+            self.status_message = Some(format!(
+                "Move the tasks out of {} before deleting it",
+                column.name
+            ));
+            return Ok(());
+        }
+        db.delete_column(&column)?;
+        self.reload_columns(db);
+        Ok(())
+    }
+
+    pub fn current_board_name(&self) -> &str {
+        self.boards
+            .iter()
+            .find(|b| b.id == Some(self.current_board))
+            .map(|b| b.name.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn selected_board(&self) -> Option<&Board> {
+        self.boards.get(self.selected_board_index)
+    }
+
+    pub fn select_next_board(&mut self) {
+        if self.selected_board_index + 1 < self.boards.len() {
+            self.selected_board_index += 1;
+        }
+    }
+
+    pub fn select_prev_board(&mut self) {
+        self.selected_board_index = self.selected_board_index.saturating_sub(1);
+    }
+
+    pub fn switch_to_selected_board(&mut self, db: &Database) {
+        if let Some(id) = self.selected_board().and_then(|b| b.id) {
+            self.current_board = id;
+            self.tag_filter = None;
+            self.selected_column = 0;
+            self.selected_index = 0;
+            self.reload_columns(db);
+            self.reload_tasks(db);
+        }
+    }
+
+    pub fn create_board(&mut self, db: &mut Database, board: &Board) -> Result<(), AppError> {
+        self.check_board_name(board)?;
+        db.insert_board(board)?;
+        self.boards = db.load_boards()?;
+        self.selected_board_index = self.boards.len().saturating_sub(1);
+        Ok(())
+    }
+
+    pub fn rename_board(&mut self, db: &mut Database, board: &Board) -> Result<(), AppError> {
+        self.check_board_name(board)?;
+        db.update_board(board)?;
+        self.boards = db.load_boards()?;
+        Ok(())
+    }
+
+    /// Board names are unique.
+    fn check_board_name(&self, board: &Board) -> Result<(), AppError> {
+        if self
+            .boards
+            .iter()
+            .any(|b| b.name == board.name && b.id != board.id)
+        {
+            return Err(AppError::BoardExists(board.name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Deletes the highlighted board and its tasks. The last board is kept.
+    pub fn delete_selected_board(&mut self, db: &mut Database) -> Result<(), AppError> {
+        if self.boards.len() <= 1 {
+            return Ok(());
+        }
+        let Some(board) = self.selected_board().cloned() else {
+            return Ok(());
+        };
+        db.delete_board(&board)?;
+        self.boards = db.load_boards()?;
+        self.selected_board_index = 0;
+        if board.id == Some(self.current_board) {
+            self.switch_to_selected_board(db);
+        }
+        Ok(())
+    }
+
+    /// Moves the task selected on the current board to the highlighted board.
+    pub fn move_selected_task_to_board(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let (Some(task), Some(board_id)) = (
+            self.selected_task().cloned(),
+            self.selected_board().and_then(|b| b.id),
+        ) else {
+            return Ok(());
+        };
+        if task.board_id == board_id {
+            return Ok(());
+        }
+        let before = task.clone();
+        let mut task = task;
+        task.board_id = board_id;
+        // Lets the database pick the matching column on the other board.
+        task.column_id = None;
+        task.position = None;
+        self.undo_stack.execute(db, Command::update(before, task))?;
+        self.reload_tasks(db);
+        self.selected_index = 0;
+        Ok(())
+    }
+
+    pub fn open_checklist(&mut self, db: &Database) -> Result<(), AppError> {
+        let Some(task) = self.selected_task().cloned() else {
+            return Ok(());
+        };
+        self.checklist = db.load_checklist(task.id.unwrap_or_default())?;
+        self.checklist_task = Some(task);
+        self.selected_checklist_index = 0;
+        self.mode = AppMode::Checklist;
+        Ok(())
+    }
+
+    pub fn close_checklist(&mut self, db: &Database) {
+        self.checklist_task = None;
+        self.checklist.clear();
+        self.reload_tasks(db);
+        self.mode = AppMode::Board;
+    }
+
+    fn reload_checklist(&mut self, db: &Database) -> Result<(), AppError> {
+        if let Some(task_id) = self.checklist_task.as_ref().and_then(|t| t.id) {
+            self.checklist = db.load_checklist(task_id)?;
+        }
+        if self.selected_checklist_index >= self.checklist.len() {
+            self.selected_checklist_index = self.checklist.len().saturating_sub(1);
+        }
+        Ok(())
+    }
+
+    pub fn selected_checklist_item(&self) -> Option<&ChecklistItem> {
+        self.checklist.get(self.selected_checklist_index)
+    }
+
+    pub fn select_next_checklist_item(&mut self) {
+        if self.selected_checklist_index + 1 < self.checklist.len() {
+            self.selected_checklist_index += 1;
+        }
+    }
+
+    pub fn select_prev_checklist_item(&mut self) {
+        self.selected_checklist_index = self.selected_checklist_index.saturating_sub(1);
+    }
+
+    pub fn toggle_checklist_item(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let Some(mut item) = self.selected_checklist_item().cloned() else {
+            return Ok(());
+        };
+        item.done = !item.done;
+        db.update_checklist_item(&item)?;
+        self.reload_checklist(db)
+    }
+
+    pub fn add_checklist_item(
+        &mut self,
+        db: &mut Database,
+        description: String,
+    ) -> Result<(), AppError> {
+        let Some(task_id) = self.checklist_task.as_ref().and_then(|t| t.id) else {
+            return Ok(());
+        };
+        db.insert_checklist_item(&ChecklistItem::new(task_id, description))?;
+        self.reload_checklist(db)?;
+        self.selected_checklist_index = self.checklist.len().saturating_sub(1);
+        Ok(())
+    }
+
+    pub fn update_checklist_item(
+        &mut self,
+        db: &mut Database,
+        item: &ChecklistItem,
+    ) -> Result<(), AppError> {
+        db.update_checklist_item(item)?;
+        self.reload_checklist(db)
+    }
+
+    pub fn delete_checklist_item(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let Some(item) = self.selected_checklist_item().cloned() else {
+            return Ok(());
+        };
+        db.delete_checklist_item(&item)?;
+        self.reload_checklist(db)
+    }
+
+    pub fn open_dependencies(&mut self, db: &Database) -> Result<(), AppError> {
+        let Some(task) = self.selected_task().cloned() else {
+            return Ok(());
+        };
+        self.dependencies = db.load_dependencies(task.id.unwrap_or_default())?;
+        self.dependencies_task = Some(task);
+        self.selected_dependency_index = 0;
+        self.mode = AppMode::Dependencies;
+        Ok(())
+    }
+
+    pub fn close_dependencies(&mut self, db: &Database) {
+        self.dependencies_task = None;
+        self.dependencies.clear();
+        self.reload_tasks(db);
+        self.mode = AppMode::Board;
+    }
+
+    /// Tasks of the current board that may block the task being edited.
+    pub fn dependency_candidates(&self) -> Vec<&Task> {
+        let task_id = self.dependencies_task.as_ref().and_then(|t| t.id);
+        self.tasks.iter().filter(|t| t.id != task_id).collect()
+    }
+
+    pub fn select_next_dependency(&mut self) {
+        if self.selected_dependency_index + 1 < self.dependency_candidates().len() {
+            self.selected_dependency_index += 1;
+        }
+    }
+
+    pub fn select_prev_dependency(&mut self) {
+        self.selected_dependency_index = self.selected_dependency_index.saturating_sub(1);
+    }
+
+    /// Adds or removes the highlighted candidate as a blocker. A link that
+    /// would create a cycle is refused and reported in the status message.
+    pub fn toggle_dependency(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let (Some(task_id), Some(blocked_by_id)) = (
+            self.dependencies_task.as_ref().and_then(|t| t.id),
+            self.dependency_candidates()
+                .get(self.selected_dependency_index)
+                .and_then(|t| t.id),
+        ) else {
+            return Ok(());
+        };
+
+        if self.dependencies.contains(&blocked_by_id) {
+            db.remove_dependency(task_id, blocked_by_id)?;
+        } else {
+            match db.add_dependency(task_id, blocked_by_id) {
+                Err(e @ AppError::DependencyCycle { .. }) => {
+                    self.status_message = Some(e.to_string());
+                }
+                res => res?,
+            }
+        }
+        self.dependencies = db.load_dependencies(task_id)?;
+        Ok(())
+    }
+
+    pub fn open_history(&mut self, db: &Database) -> Result<(), AppError> {
+        let Some(task) = self.selected_task().cloned() else {
+            return Ok(());
+        };
+        self.history = db.load_events(task.id.unwrap_or_default())?;
+        self.history_task = Some(task);
+        self.history_scroll = 0;
+        self.mode = AppMode::History;
+        Ok(())
+    }
+
+    pub fn close_history(&mut self) {
+        self.history_task = None;
+        self.history.clear();
+        self.mode = AppMode::Board;
+    }
+
+    pub fn scroll_history_down(&mut self) {
+        if (self.history_scroll as usize) + 1 < self.history.len() {
+            self.history_scroll += 1;
+        }
+    }
+
+    pub fn scroll_history_up(&mut self) {
+        self.history_scroll = self.history_scroll.saturating_sub(1);
+    }
+
+    /// Starts or stops the timer of the task selected on the board, or of the
+    /// task whose time entries are open.
+    pub fn toggle_timer(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let task = match self.mode {
+            AppMode::TimeEntries => self.time_task.clone(),
+            _ => self.selected_task().cloned(),
+        };
+        let Some(task_id) = task.and_then(|t| t.id) else {
+            return Ok(());
+        };
+        if db
+            .load_time_entries(task_id)?
+            .iter()
+            .any(TimeEntry::is_running)
+        {
+            db.stop_timer(task_id)?;
+        } else {
+            db.start_timer(task_id)?;
+        }
+        if matches!(self.mode, AppMode::TimeEntries) {
+            self.time_entries = db.load_time_entries(task_id)?;
+        }
+        self.reload_tasks(db);
+        Ok(())
+    }
+
+    pub fn open_time_entries(&mut self, db: &Database) -> Result<(), AppError> {
+        let Some(task) = self.selected_task().cloned() else {
+            return Ok(());
+        };
+        self.time_entries = db.load_time_entries(task.id.unwrap_or_default())?;
+        self.time_task = Some(task);
+        self.selected_time_entry_index = 0;
+        self.mode = AppMode::TimeEntries;
+        Ok(())
+    }
+
+    pub fn close_time_entries(&mut self, db: &Database) {
+        self.time_task = None;
+        self.time_entries.clear();
+        self.reload_tasks(db);
+        self.mode = AppMode::Board;
+    }
+
+    pub fn select_next_time_entry(&mut self) {
+        if self.selected_time_entry_index + 1 < self.time_entries.len() {
+            self.selected_time_entry_index += 1;
+        }
+    }
+
+    pub fn select_prev_time_entry(&mut self) {
+        self.selected_time_entry_index = self.selected_time_entry_index.saturating_sub(1);
+    }
+
+    /// Lengthens (or shortens, for negative `minutes`) the highlighted entry.
+    /// A finished entry moves its end, a running one its start; neither can
+    /// drop below zero length or start in the future.
+    pub fn adjust_time_entry(&mut self, db: &mut Database, minutes: i64) -> Result<(), AppError> {
+        let Some(mut entry) = self
+            .time_entries
+            .get(self.selected_time_entry_index)
+            .cloned()
+        else {
+            return Ok(());
+        };
+        let delta = Duration::minutes(minutes);
+        match entry.ended_at {
+            Some(ended_at) => entry.ended_at = Some((ended_at + delta).max(entry.started_at)),
+            None => entry.started_at = (entry.started_at - delta).min(Local::now()),
+        }
+        db.update_time_entry(&entry)?;
+        self.time_entries = db.load_time_entries(entry.task_id)?;
+        // Moving the start of a running entry can change its place in the list.
+        self.selected_time_entry_index = self
+            .time_entries
+            .iter()
+            .position(|e| e.id == entry.id)
+            .unwrap_or_default();
+        Ok(())
+    }
+
+    pub fn delete_time_entry(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let Some(entry) = self
+            .time_entries
+            .get(self.selected_time_entry_index)
+            .cloned()
+        else {
+            return Ok(());
+        };
+        if let Some(id) = entry.id {
+            db.delete_time_entry(id)?;
+        }
+        self.time_entries = db.load_time_entries(entry.task_id)?;
+        if self.selected_time_entry_index >= self.time_entries.len() {
+            self.selected_time_entry_index = self.time_entries.len().saturating_sub(1);
+        }
+        Ok(())
+    }
+
+    /// Builds the per-day, per-task time report of the current board. Entries
+    /// count towards the day they started on.
+    pub fn open_time_report(&mut self, db: &Database) -> Result<(), AppError> {
+        let now = Local::now();
+        let mut totals: BTreeMap<(chrono::NaiveDate, i64), (String, Duration)> = BTreeMap::new();
+        for (entry, description) in db.load_board_time_entries(self.current_board)? {
+            let total = totals
+                .entry((entry.started_at.date_naive(), entry.task_id))
+                .or_insert((description, Duration::zero()));
+            total.1 += entry.duration(now);
+        }
+        self.time_report = totals
+            .into_iter()
+            .rev()
+            .map(|((day, task_id), (description, duration))| TimeSummary {
+                day,
+                task_id,
+                description,
+                duration,
+            })
+            .collect();
+        self.time_report_scroll = 0;
+        self.mode = AppMode::TimeReport;
+        Ok(())
+    }
+
+    pub fn scroll_time_report_down(&mut self) {
+        if (self.time_report_scroll as usize) + 1 < self.time_report.len() {
+            self.time_report_scroll += 1;
+        }
+    }
+
+    pub fn scroll_time_report_up(&mut self) {
+        self.time_report_scroll = self.time_report_scroll.saturating_sub(1);
+    }
+
+    pub fn close_time_report(&mut self) {
+        self.time_report.clear();
+        self.mode = AppMode::Board;
+    }
+
+    pub fn open_trash(&mut self, db: &Database) -> Result<(), AppError> {
+        self.trash = db.load_trash(self.current_board)?;
+        self.selected_trash_index = 0;
+        self.mode = AppMode::Trash;
+        Ok(())
+    }
+
+    pub fn close_trash(&mut self, db: &Database) {
+        self.trash.clear();
+        self.reload_tasks(db);
+        self.mode = AppMode::Board;
+    }
+
+    pub fn select_next_trash_task(&mut self) {
+        if self.selected_trash_index + 1 < self.trash.len() {
+            self.selected_trash_index += 1;
+        }
+    }
+
+    pub fn select_prev_trash_task(&mut self) {
+        self.selected_trash_index = self.selected_trash_index.saturating_sub(1);
+    }
+
+    pub fn restore_trash_task(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let Some(id) = self.trash.get(self.selected_trash_index).and_then(|t| t.id) else {
+            return Ok(());
+        };
+        db.restore_task(id)?;
+        self.reload_trash(db)
+    }
+
+    pub fn purge_trash_task(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let Some(task) = self.trash.get(self.selected_trash_index).cloned() else {
+            return Ok(());
+        };
+        db.purge_task(&task)?;
+        self.reload_trash(db)
+    }
+
+    fn reload_trash(&mut self, db: &Database) -> Result<(), AppError> {
+        self.trash = db.load_trash(self.current_board)?;
+        if self.selected_trash_index >= self.trash.len() {
+            self.selected_trash_index = self.trash.len().saturating_sub(1);
+        }
+        Ok(())
+    }
+
+    /// Archives cards that sat in Done for `archive_after_days` and, if so
+    /// configured, sends cards left in Today since before midnight back to
+    /// Backlog. Runs at most once a day and reports what moved.
+    pub fn run_policies(&mut self, db: &mut Database) -> Result<(), AppError> {
+        let now = Local::now();
+        if self.policies_run_on == Some(now.date_naive()) {
+            return Ok(());
+        }
+        self.policies_run_on = Some(now.date_naive());
+
+        let mut report = Vec::new();
+        if self.config.archive_after_days > 0 {
+            let before = now - Duration::days(self.config.archive_after_days as i64);
+            let moved = move_stale_tasks(db, Status::Done, before, Status::Archived)?;
+            if moved > 0 {
+                report.push(format!("Archived {moved} done task(s)"));
+            }
+        }
+        if self.config.today_rollover == TodayRollover::Backlog {
+            let midnight = now
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .and_then(|dt| dt.and_local_timezone(Local).earliest())
+                .unwrap_or(now);
+            let moved = move_stale_tasks(db, Status::Today, midnight, Status::Backlog)?;
+            if moved > 0 {
+                report.push(format!("Moved {moved} unfinished Today task(s) to Backlog"));
+            }
+        }
+
+        if !report.is_empty() {
+            report.extend(self.status_message.take());
+            self.status_message = Some(report.join("; "));
+            self.reload_tasks(db);
+        }
+        Ok(())
+    }
+
+    pub fn switch_mode(&mut self) {
+        match self.mode {
+            AppMode::Board => self.mode = AppMode::NewTask,
+            AppMode::NewTask | AppMode::EditTask => self.mode = AppMode::Board,
+            AppMode::Boards => self.mode = AppMode::Board,
+            AppMode::NewBoard | AppMode::EditBoard => self.mode = AppMode::Boards,
+            AppMode::Checklist
+            | AppMode::Dependencies
+            | AppMode::History
+            | AppMode::TimeEntries
+            | AppMode::TimeReport
+            | AppMode::Trash
+            | AppMode::Columns => self.mode = AppMode::Board,
+            AppMode::NewColumn | AppMode::EditColumn => self.mode = AppMode::Columns,
+            AppMode::NewChecklistItem | AppMode::EditChecklistItem => {
+                self.mode = AppMode::Checklist
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ModalField {
+    Description,
+    Deadline,
+    Tags,
+    Recurrence,
+}
+
+impl ModalField {
+    pub fn next(self) -> Self {
+        match self {
+            ModalField::Description => ModalField::Deadline,
+            ModalField::Deadline => ModalField::Tags,
+            ModalField::Tags => ModalField::Recurrence,
+            ModalField::Recurrence => ModalField::Description,
+        }
+    }
+}
+
+pub struct TaskModalState {
+    pub description_in: TextInput,
+    pub deadline_in: TextInput,
+    pub tags_in: TextInput,
+    pub recurrence_in: TextInput,
+    pub priority: Priority,
+    pub focus: ModalField,
+    /// Task being edited; `None` when the modal creates a new task.
+    pub editing: Option<Task>,
+    /// Day highlighted in the date picker while it is open.
+    pub calendar: Option<NaiveDate>,
+}
 
 impl TaskModalState {
+    pub fn new() -> Self {
+        Self {
+            description_in: TextInput::default(),
+            deadline_in: TextInput::default(),
+            tags_in: TextInput::default(),
+            recurrence_in: TextInput::default(),
+            priority: Priority::None,
+            focus: ModalField::Description,
+            editing: None,
+            calendar: None,
+        }
+    }
+
+    pub fn from_task(task: &Task) -> Self {
+        Self {
+            description_in: TextInput::from(&task.description),
+            deadline_in: TextInput::from(
+                &task
+                    .deadline
+                    .map(|d| d.format("%d/%m/%Y %H:%M").to_string())
+                    .unwrap_or_default(),
+            ),
+            tags_in: TextInput::from(&task.tags.join(" ")),
+            recurrence_in: TextInput::from(
+                &task
+                    .recurrence
+                    .as_ref()
+                    .map(|r| r.to_string())
+                    .unwrap_or_default(),
+            ),
+            priority: task.priority,
+            focus: ModalField::Description,
+            editing: Some(task.clone()),
+            calendar: None,
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    pub fn focused_input(&mut self) -> &mut TextInput {
+        match self.focus {
+            ModalField::Description => &mut self.description_in,
+            ModalField::Deadline => &mut self.deadline_in,
+            ModalField::Tags => &mut self.tags_in,
+            ModalField::Recurrence => &mut self.recurrence_in,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.description_in.clear();
+        self.deadline_in.clear();
+        self.tags_in.clear();
+        self.recurrence_in.clear();
+        self.priority = Priority::None;
+        self.focus = ModalField::Description;
+        self.editing = None;
+        self.calendar = None;
+    }
+
+    /// The typed deadline; empty input means no deadline.
+    pub fn deadline(&self) -> Result<Option<DateTime<Local>>, &'static str> {
+        if self.deadline_in.as_str().trim().is_empty() {
+            return Ok(None);
+        }
+        str_to_local_dt(self.deadline_in.as_str())
+            .map(Some)
+            .ok_or("unrecognized date")
+    }
+
+    /// The typed repeat rule; an empty field means the task does not repeat.
+    pub fn recurrence(&self) -> Result<Option<Recurrence>, String> {
+        if self.recurrence_in.as_str().trim().is_empty() {
+            return Ok(None);
+        }
+        self.recurrence_in.as_str().parse().map(Some)
+    }
+
+    /// Opens the date picker on the typed deadline, or today.
+    pub fn open_calendar(&mut self) {
+        let deadline = self.deadline().ok().flatten().unwrap_or(Local::now());
+        self.calendar = Some(deadline.date_naive());
+    }
+
+    pub fn move_calendar(&mut self, days: i64) {
+        self.calendar = self.calendar.map(|d| d + Duration::days(days));
+    }
+
+    pub fn shift_calendar_month(&mut self, months: i32) {
+        self.calendar = self.calendar.and_then(|d| match months {
+            m if m < 0 => d.checked_sub_months(chrono::Months::new(m.unsigned_abs())),
+            m => d.checked_add_months(chrono::Months::new(m as u32)),
+        });
+    }
+
+    /// Writes the highlighted day into the deadline field, keeping the time
+    /// of day of the deadline typed so far.
+    pub fn pick_calendar_date(&mut self) {
+        let Some(date) = self.calendar.take() else {
+            return;
+        };
+        let time = self
+            .deadline()
+            .ok()
+            .flatten()
+            .map(|d| d.time())
+            .filter(|t| *t != NaiveTime::MIN);
+        self.deadline_in.set(&match time {
+            Some(time) => date.and_time(time).format("%d/%m/%Y %H:%M").to_string(),
+            None => date.format("%d/%m/%Y").to_string(),
+        });
+        self.focus = ModalField::Deadline;
+    }
+}
+
+pub struct BoardModalState {
+    pub name_in: String,
+    /// Board being renamed; `None` when the modal creates a new board.
+    pub editing: Option<Board>,
+}
+
+impl BoardModalState {
+    pub fn new() -> Self {
+        Self {
+            name_in: String::new(),
+            editing: None,
+        }
+    }
+
+    pub fn from_board(board: &Board) -> Self {
+        Self {
+            name_in: board.name.to_string(),
+            editing: Some(board.clone()),
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.name_in = "".to_string();
+        self.editing = None;
+    }
+}
+
+pub struct ChecklistModalState {
+    pub description_in: String,
+    /// Item being edited; `None` when the modal adds a new item.
+    pub editing: Option<ChecklistItem>,
+}
+
+impl ChecklistModalState {
     pub fn new() -> Self {
         Self {
             description_in: String::new(),
-            deadline_in: String::new(),
-            entering_deadline: false,
+            editing: None,
+        }
+    }
+
+    pub fn from_item(item: &ChecklistItem) -> Self {
+        Self {
+            description_in: item.description.to_string(),
+            editing: Some(item.clone()),
         }
     }
 
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
     pub fn clear(&mut self) {
         self.description_in = "".to_string();
-        self.deadline_in = "".to_string();
-        self.entering_deadline = false;
+        self.editing = None;
+    }
+}
+
+pub struct ColumnModalState {
+    pub name_in: String,
+    /// Column being renamed; `None` when the modal creates a new column.
+    pub editing: Option<Column>,
+}
+
+impl ColumnModalState {
+    pub fn new() -> Self {
+        Self {
+            name_in: String::new(),
+            editing: None,
+        }
+    }
+
+    pub fn from_column(column: &Column) -> Self {
+        Self {
+            name_in: column.name.to_string(),
+            editing: Some(column.clone()),
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.name_in = "".to_string();
+        self.editing = None;
+    }
+}
+
+/// Order of the cards in a column: highest priority first; within a priority
+/// the cards the user reordered by hand in their manual order, then the rest
+/// by deadline, soonest first and cards without one last.
+pub fn card_order(a: &Task, b: &Task) -> Ordering {
+    b.priority
+        .cmp(&a.priority)
+        .then_with(|| b.ordered_manually.cmp(&a.ordered_manually))
+        .then_with(|| match (a.ordered_manually, a.deadline, b.deadline) {
+            (true, _, _) => Ordering::Equal,
+            (false, Some(a), Some(b)) => a.cmp(&b),
+            (false, Some(_), None) => Ordering::Less,
+            (false, None, Some(_)) => Ordering::Greater,
+            (false, None, None) => Ordering::Equal,
+        })
+        .then_with(|| a.position.cmp(&b.position))
+}
+
+/// Moves every task stale in `status` to the first `target` column of its
+/// board and returns how many moved.
+fn move_stale_tasks(
+    db: &mut Database,
+    status: Status,
+    before: chrono::DateTime<Local>,
+    target: Status,
+) -> Result<usize, AppError> {
+    let tasks = db.load_stale_tasks(status, before, target)?;
+    for mut task in tasks.iter().cloned() {
+        task.status = target;
+        task.column_id = None;
+        task.position = None;
+        db.update_task(&task)?;
     }
+    Ok(tasks.len())
 }
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Local};
+
+#[derive(Debug, Clone)]
+pub struct Board {
+    pub id: Option<i64>,
+    pub name: String,
+    pub created_at: DateTime<Local>,
+}
+
+impl Board {
+    pub fn new(name: String) -> Board {
+        Board {
+            id: None,
+            name,
+            created_at: Local::now(),
+        }
+    }
+}
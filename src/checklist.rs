@@ -0,0 +1,35 @@
+use std::fmt;
+
+use chrono::{DateTime, Local};
+
+#[derive(Debug, Clone)]
+pub struct ChecklistItem {
+    pub id: Option<i64>,
+    pub task_id: i64,
+    pub description: String,
+    pub done: bool,
+    pub created_at: DateTime<Local>,
+}
+
+impl ChecklistItem {
+    pub fn new(task_id: i64, description: String) -> ChecklistItem {
+        ChecklistItem {
+            id: None,
+            task_id,
+            description,
+            done: false,
+            created_at: Local::now(),
+        }
+    }
+}
+
+impl fmt::Display for ChecklistItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}",
+            if self.done { "x" } else { " " },
+            self.description
+        )
+    }
+}
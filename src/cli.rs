@@ -0,0 +1,476 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use strum::{EnumMessage, IntoEnumIterator};
+
+use crate::{
+    app::card_order,
+    board::Board,
+    column::Column,
+    config::Config,
+    db::Database,
+    error::AppError,
+    formats::{
+        Format, ImportMode, ImportReport,
+        csv::{self, CsvField, DEFAULT_FIELDS},
+        ics, json, markdown, taskwarrior,
+        todotxt::{self, SyncReport},
+    },
+    recurrence::Recurrence,
+    task::{Priority, Status, Task},
+    undo::Command,
+    utils::{format_duration, str_to_local_dt},
+};
+
+/// A kanban board in your terminal. Without a subcommand it opens the board.
+#[derive(Parser)]
+#[command(name = "rstd", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Subcommand)]
+pub enum CliCommand {
+    /// Create a task
+    Add {
+        description: String,
+        /// Deadline, e.g. "24/10/2026 17:00", "tomorrow" or "next fri"
+        #[arg(long, value_parser = parse_due)]
+        due: Option<DateTime<Local>>,
+        #[arg(long, value_parser = parse_status, default_value = "backlog")]
+        status: Status,
+        #[arg(long, value_parser = parse_priority, default_value = "none")]
+        priority: Priority,
+        /// Tags separated by spaces or commas
+        #[arg(long)]
+        tags: Option<String>,
+        /// Repeat rule, e.g. "daily", "weekly mon,fri" or "every 3 days"
+        #[arg(long)]
+        repeat: Option<Recurrence>,
+        /// Board name; the first board by default
+        #[arg(long)]
+        board: Option<String>,
+    },
+    /// List the tasks of a board
+    List {
+        /// Only tasks with this status; may be repeated
+        #[arg(long, value_parser = parse_status)]
+        status: Vec<Status>,
+        /// Board name; the first board by default
+        #[arg(long)]
+        board: Option<String>,
+        /// Print the tasks as a JSON array
+        #[arg(long)]
+        json: bool,
+    },
+    /// Move a task to the first column with the given status
+    Move {
+        id: i64,
+        #[arg(value_parser = parse_status)]
+        status: Status,
+    },
+    /// Move a task to Done
+    Done { id: i64 },
+    /// Move a task to the trash
+    Rm { id: i64 },
+    /// Print a task with its checklist and blockers
+    Show { id: i64 },
+    /// Write the tasks to a file, or to stdout
+    Export {
+        #[arg(long, value_enum, default_value = "json")]
+        format: Format,
+        /// File to write instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// CSV columns, comma separated: id, board, column, description, status,
+        /// priority, tags, created_at, updated_at, deadline
+        #[arg(long, value_delimiter = ',')]
+        fields: Vec<CsvField>,
+        /// Board to export as Markdown; the first board by default
+        #[arg(long)]
+        board: Option<String>,
+        /// Markdown columns to include, by name or status, comma separated;
+        /// all by default
+        #[arg(long, visible_alias = "status", value_delimiter = ',')]
+        column: Vec<String>,
+        /// Only Markdown tasks changed since this date, e.g. "yesterday"
+        #[arg(long, value_parser = parse_due)]
+        since: Option<DateTime<Local>>,
+    },
+    /// Load tasks from a file
+    Import {
+        file: PathBuf,
+        #[arg(long, value_enum, default_value = "json")]
+        format: Format,
+        /// Replace needs a full JSON backup
+        #[arg(long, value_enum, default_value = "merge")]
+        mode: ImportMode,
+        /// Report what would be imported and which rows are rejected, without
+        /// changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Merge a file both ways: changes on either side since the last sync are
+    /// applied to the other, the newer side wins a conflict
+    Sync {
+        #[arg(value_enum)]
+        format: SyncFormat,
+        path: PathBuf,
+    },
+}
+
+/// Formats that can be kept in sync with the database.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SyncFormat {
+    /// todo.txt file, e.g. one shared with a phone app
+    Todotxt,
+}
+
+fn parse_due(s: &str) -> Result<DateTime<Local>, String> {
+    str_to_local_dt(s).ok_or_else(|| format!("unrecognized date \"{s}\""))
+}
+
+fn parse_status(s: &str) -> Result<Status, String> {
+    s.parse().map_err(|_| {
+        let statuses: Vec<String> = Status::iter().map(|s| s.to_string()).collect();
+        format!("expected one of {}", statuses.join(", "))
+    })
+}
+
+fn parse_priority(s: &str) -> Result<Priority, String> {
+    s.parse().map_err(|_| {
+        let priorities: Vec<String> = Priority::iter().map(|p| p.to_string()).collect();
+        format!("expected one of {}", priorities.join(", "))
+    })
+}
+
+/// Runs a subcommand against the same database as the board.
+pub fn run(command: CliCommand) -> Result<(), AppError> {
+    let mut db = Database::new()?;
+    db.apply_migrations()?;
+    let config = Config::load();
+    db.insert_position = config.insert_position;
+    let mut out = io::stdout().lock();
+
+    match command {
+        CliCommand::Add {
+            description,
+            due,
+            status,
+            priority,
+            tags,
+            repeat,
+            board,
+        } => {
+            let board = find_board(&db, board.as_deref())?;
+            let board_id = board.id.unwrap_or_default();
+            let column = status_column(&db, board_id, status)?;
+            let mut task = Task::new(description, Some(status), due, board_id);
+            task.column_id = column.id;
+            task.priority = priority;
+            task.tags = Task::parse_tags(tags.as_deref().unwrap_or_default());
+            task.recurrence = repeat;
+            let id = db.insert_task(&task)?;
+            writeln!(out, "Created task #{id} in {}", column.name)?;
+        }
+        CliCommand::List {
+            status,
+            board,
+            json,
+        } => {
+            let board = find_board(&db, board.as_deref())?;
+            let board_id = board.id.unwrap_or_default();
+            let columns = db.load_columns(board_id)?;
+            let mut tasks: Vec<Task> = db
+                .load_tasks(board_id)?
+                .into_iter()
+                .filter(|t| status.is_empty() || status.contains(&t.status))
+                .collect();
+            let column = |t: &Task| columns.iter().position(|c| c.id == t.column_id);
+            tasks.sort_by(|a, b| column(a).cmp(&column(b)).then_with(|| card_order(a, b)));
+            if json {
+                let tasks: Vec<serde_json::Value> = tasks
+                    .iter()
+                    .map(|t| task_json(t, &board, &columns))
+                    .collect();
+                writeln!(out, "{}", serde_json::Value::Array(tasks))?;
+            } else {
+                for task in &tasks {
+                    writeln!(out, "{}", task_line(task, &columns))?;
+                }
+            }
+        }
+        CliCommand::Move { id, status } => move_task(&mut db, &mut out, &config, id, status)?,
+        CliCommand::Done { id } => move_task(&mut db, &mut out, &config, id, Status::Done)?,
+        CliCommand::Rm { id } => {
+            let task = db.load_task(id)?;
+            db.delete_task(&task)?;
+            writeln!(out, "Moved task #{id} to the trash")?;
+        }
+        CliCommand::Show { id } => {
+            let task = db.load_task(id)?;
+            let columns = db.load_columns(task.board_id)?;
+            let board = db
+                .load_boards()?
+                .into_iter()
+                .find(|b| b.id == Some(task.board_id));
+            print_task(&db, &mut out, &task, board.as_ref(), &columns)?;
+        }
+        CliCommand::Export {
+            format,
+            output,
+            fields,
+            board,
+            column,
+            since,
+        } => {
+            let mut out: Box<dyn Write + '_> = match output {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(&mut out),
+            };
+            match format {
+                Format::Json => json::export(&db, &mut out)?,
+                Format::Csv if fields.is_empty() => csv::export(&db, &mut out, &DEFAULT_FIELDS)?,
+                Format::Csv => csv::export(&db, &mut out, &fields)?,
+                Format::Markdown => {
+                    let board = find_board(&db, board.as_deref())?;
+                    markdown::export(&db, &mut out, &board, &column, since)?
+                }
+                Format::Todotxt => todotxt::export(&db, &mut out)?,
+                Format::Ics => ics::export(&db, &mut out)?,
+                Format::Taskwarrior => taskwarrior::export(&db, &mut out)?,
+            }
+        }
+        CliCommand::Import {
+            file,
+            format,
+            mode,
+            dry_run,
+        } => {
+            let input = fs::read_to_string(&file)?;
+            if mode == ImportMode::Replace && format != Format::Json {
+                return Err(AppError::InvalidImport(
+                    "--mode replace needs a full JSON backup".to_string(),
+                ));
+            }
+            let report = match format {
+                Format::Json => json::import(&mut db, &input, mode, dry_run)?,
+                Format::Csv => csv::import(&mut db, &input, dry_run)?,
+                Format::Todotxt => todotxt::import(&mut db, &input, dry_run)?,
+                Format::Ics => ics::import(&mut db, &input, dry_run)?,
+                Format::Taskwarrior => taskwarrior::import(&mut db, &input, dry_run)?,
+                Format::Markdown => {
+                    return Err(AppError::InvalidImport(
+                        "Markdown can only be exported".to_string(),
+                    ));
+                }
+            };
+            print_report(&mut out, &report, dry_run)?;
+        }
+        CliCommand::Sync { format, path } => {
+            let report = match format {
+                SyncFormat::Todotxt => todotxt::sync(&mut db, &path)?,
+            };
+            print_sync_report(&mut out, &report, &path)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_report(out: &mut impl Write, report: &ImportReport, dry_run: bool) -> io::Result<()> {
+    let (created, updated) = if dry_run {
+        ("Would create", "would update")
+    } else {
+        ("Created", "updated")
+    };
+    writeln!(
+        out,
+        "{created} {} task(s) and {} board(s), {updated} {} task(s)",
+        report.tasks, report.boards, report.updated
+    )?;
+    for skipped in &report.skipped {
+        writeln!(out, "Skipped {skipped}")?;
+    }
+    Ok(())
+}
+
+fn print_sync_report(out: &mut impl Write, report: &SyncReport, path: &Path) -> io::Result<()> {
+    writeln!(
+        out,
+        "rstd: created {}, updated {}, trashed {} task(s)",
+        report.created, report.updated, report.deleted
+    )?;
+    writeln!(
+        out,
+        "{}: wrote {}, removed {} line(s)",
+        path.display(),
+        report.written,
+        report.removed
+    )?;
+    for conflict in &report.conflicts {
+        writeln!(out, "Conflict: {conflict}")?;
+    }
+    Ok(())
+}
+
+/// The board called `name` (ignoring case), or the first board.
+fn find_board(db: &Database, name: Option<&str>) -> Result<Board, AppError> {
+    let boards = db.load_boards()?;
+    let board = match name {
+        Some(name) => boards
+            .into_iter()
+            .find(|b| b.name.eq_ignore_ascii_case(name)),
+        None => boards.into_iter().next(),
+    };
+    board.ok_or_else(|| AppError::MissingBoard(name.unwrap_or_default().to_string()))
+}
+
+/// The first column of the board with `status`, where moved tasks land.
+fn status_column(db: &Database, board_id: i64, status: Status) -> Result<Column, AppError> {
+    db.load_columns(board_id)?
+        .into_iter()
+        .find(|c| c.status == status)
+        .ok_or_else(|| AppError::MissingColumn(status.get_message().unwrap().to_string()))
+}
+
+/// Moves a task the way the board does, see `Command::move_task`.
+fn move_task(
+    db: &mut Database,
+    out: &mut impl Write,
+    config: &Config,
+    id: i64,
+    status: Status,
+) -> Result<(), AppError> {
+    let task = db.load_task(id)?;
+    let column = status_column(db, task.board_id, status)?;
+    if task.column_id == column.id {
+        writeln!(out, "Task #{id} is already in {}", column.name)?;
+        return Ok(());
+    }
+    let mut moved = Command::move_task(db, &task, &column, config.wip_limits)?;
+    if let Some(warning) = &moved.wip_warning {
+        eprintln!("warning: {warning}");
+    }
+    moved.command.apply(db)?;
+    writeln!(out, "Moved task #{id} to {}", column.name)?;
+    if let Some(next) = moved.next_occurrence() {
+        writeln!(
+            out,
+            "Next occurrence #{} scheduled for {}",
+            next.id.unwrap_or_default(),
+            next.deadline
+                .map(|d| d.format("%d/%m/%Y %H:%M").to_string())
+                .unwrap_or_default()
+        )?;
+    }
+    Ok(())
+}
+
+fn column_name(task: &Task, columns: &[Column]) -> String {
+    columns.iter().find(|c| c.id == task.column_id).map_or_else(
+        || task.status.get_message().unwrap().to_string(),
+        |c| c.name.to_string(),
+    )
+}
+
+/// One line per task: id, column, priority, first line, deadline and tags.
+fn task_line(task: &Task, columns: &[Column]) -> String {
+    let mut line = format!(
+        "#{:<4} {:<12} {:<7} {}",
+        task.id.unwrap_or_default(),
+        column_name(task, columns),
+        task.priority,
+        task.description.lines().next().unwrap_or_default()
+    );
+    if let Some(deadline) = task.deadline {
+        line.push_str(&format!("  due {}", deadline.format("%d/%m/%Y %H:%M")));
+    }
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|t| format!("#{t}")).collect();
+        line.push_str(&format!("  {}", tags.join(" ")));
+    }
+    line
+}
+
+fn task_json(task: &Task, board: &Board, columns: &[Column]) -> serde_json::Value {
+    json!({
+        "id": task.id,
+        "board": board.name,
+        "column": column_name(task, columns),
+        "status": task.status.to_string(),
+        "priority": task.priority.to_string(),
+        "description": task.description,
+        "deadline": task.deadline.map(|d| d.to_rfc3339()),
+        "tags": task.tags,
+        "recurrence": task.recurrence.as_ref().map(|r| r.to_string()),
+        "checklist_done": task.checklist_done,
+        "checklist_total": task.checklist_total,
+        "blocked_by": task.blocked_by,
+        "time_spent": task.tracked_time(Local::now()).num_seconds(),
+        "created_at": task.created_at.to_rfc3339(),
+        "updated_at": task.updated_at.to_rfc3339(),
+    })
+}
+
+fn print_task(
+    db: &Database,
+    out: &mut impl Write,
+    task: &Task,
+    board: Option<&Board>,
+    columns: &[Column],
+) -> Result<(), AppError> {
+    writeln!(out, "#{} {}", task.id.unwrap_or_default(), task.description)?;
+    writeln!(out)?;
+    if let Some(board) = board {
+        writeln!(out, "Board:      {}", board.name)?;
+    }
+    writeln!(out, "Column:     {}", column_name(task, columns))?;
+    writeln!(out, "Priority:   {}", task.priority.get_message().unwrap())?;
+    if let Some(deadline) = task.deadline {
+        writeln!(out, "Deadline:   {}", deadline.format("%a %d/%m/%Y %H:%M"))?;
+    }
+    if !task.tags.is_empty() {
+        writeln!(out, "Tags:       {}", task.tags.join(" "))?;
+    }
+    if let Some(recurrence) = &task.recurrence {
+        writeln!(out, "Repeat:     {recurrence}")?;
+    }
+    if task.is_blocked() {
+        let blockers: Vec<String> = task.blocked_by.iter().map(|id| format!("#{id}")).collect();
+        writeln!(out, "Blocked by: {}", blockers.join(", "))?;
+    }
+    let tracked = task.tracked_time(Local::now());
+    if !tracked.is_zero() {
+        let running = match task.timer_started_at {
+            Some(_) => " (running)",
+            None => "",
+        };
+        writeln!(out, "Tracked:    {}{running}", format_duration(tracked))?;
+    }
+    writeln!(
+        out,
+        "Created:    {}",
+        task.created_at.format("%d/%m/%Y %H:%M")
+    )?;
+    if let Some(id) = task.id {
+        let checklist = db.load_checklist(id)?;
+        if !checklist.is_empty() {
+            writeln!(
+                out,
+                "Checklist:  {}/{}",
+                task.checklist_done, task.checklist_total
+            )?;
+            for item in checklist {
+                writeln!(out, "  {item}")?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::task::Status;
+
+/// A board column. Its `status` is the workflow stage the column belongs to,
+/// so a custom "Review" column can count as in progress.
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub id: Option<i64>,
+    pub board_id: i64,
+    pub name: String,
+    pub position: i64,
+    /// Any color name or `#rrggbb` value understood by ratatui.
+    pub color: String,
+    pub status: Status,
+    /// Work-in-progress limit: how many cards the column should hold at most.
+    pub wip_limit: Option<u32>,
+}
+
+impl Column {
+    pub const COLORS: [&'static str; 7] =
+        ["green", "cyan", "yellow", "magenta", "red", "white", "gray"];
+
+    pub fn new(board_id: i64, name: String, position: i64, status: Status) -> Column {
+        Column {
+            id: None,
+            board_id,
+            name,
+            position,
+            color: Self::COLORS[0].to_string(),
+            status,
+            wip_limit: None,
+        }
+    }
+
+    /// Whether `count` cards break the column's WIP limit.
+    pub fn is_over_limit(&self, count: usize) -> bool {
+        self.wip_limit.is_some_and(|limit| count > limit as usize)
+    }
+
+    pub fn next_color(&self) -> String {
+        let idx = Self::COLORS
+            .iter()
+            .position(|c| *c == self.color)
+            .map(|i| (i + 1) % Self::COLORS.len())
+            .unwrap_or_default();
+        Self::COLORS[idx].to_string()
+    }
+}
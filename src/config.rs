@@ -0,0 +1,115 @@
+use std::{fs, str::FromStr};
+
+use strum_macros::{Display, EnumString};
+
+const CONFIG_PATH: &str = "./.rstd/config";
+
+/// Where a card lands when it is created in or moved into a column.
+#[derive(Display, EnumString, Debug, Clone, Copy, PartialEq)]
+pub enum InsertPosition {
+    #[strum(serialize = "top")]
+    Top,
+    #[strum(serialize = "bottom")]
+    Bottom,
+}
+
+/// What happens when a move would put more cards in a column than its WIP
+/// limit allows.
+#[derive(Display, EnumString, Debug, Clone, Copy, PartialEq)]
+pub enum WipMode {
+    /// The move is refused.
+    #[strum(serialize = "strict")]
+    Strict,
+    /// The move goes through with a warning.
+    #[strum(serialize = "soft")]
+    Soft,
+}
+
+/// What happens at midnight to cards still in a Today column.
+#[derive(Display, EnumString, Debug, Clone, Copy, PartialEq)]
+pub enum TodayRollover {
+    /// They go back to Backlog.
+    #[strum(serialize = "backlog")]
+    Backlog,
+    /// They stay in Today.
+    #[strum(serialize = "keep")]
+    Keep,
+}
+
+/// User settings read from `./.rstd/config`, one `key = value` per line.
+/// Missing files, unknown keys and invalid values fall back to the defaults.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub insert_position: InsertPosition,
+    /// Days a deleted task stays in the trash before it is purged on startup;
+    /// `0` keeps it until purged by hand.
+    pub trash_retention_days: u32,
+    pub wip_limits: WipMode,
+    /// Days a card stays in Done before it is archived; `0` never archives.
+    pub archive_after_days: u32,
+    pub today_rollover: TodayRollover,
+}
+
+impl Config {
+    pub fn load() -> Config {
+        let mut config = Config::default();
+        let Ok(contents) = fs::read_to_string(CONFIG_PATH) else {
+            return config;
+        };
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            config.set(key.trim(), value.trim());
+        }
+        config
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "insert_position" => {
+                if let Ok(v) = InsertPosition::from_str(value) {
+                    self.insert_position = v;
+                }
+            }
+            "trash_retention_days" => {
+                if let Ok(v) = value.parse() {
+                    self.trash_retention_days = v;
+                }
+            }
+            "wip_limits" => {
+                if let Ok(v) = WipMode::from_str(value) {
+                    self.wip_limits = v;
+                }
+            }
+            "archive_after_days" => {
+                if let Ok(v) = value.parse() {
+                    self.archive_after_days = v;
+                }
+            }
+            "today_rollover" => {
+                if let Ok(v) = TodayRollover::from_str(value) {
+                    self.today_rollover = v;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            insert_position: InsertPosition::Bottom,
+            trash_retention_days: 30,
+            wip_limits: WipMode::Soft,
+            archive_after_days: 0,
+            today_rollover: TodayRollover::Keep,
+        }
+    }
+}
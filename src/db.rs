@@ -1,16 +1,62 @@
-use std::str::FromStr;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
 use crate::{
+    activity::{EventKind, TaskEvent},
+    board::Board,
+    checklist::ChecklistItem,
+    column::Column,
+    config::InsertPosition,
     error::AppError,
+    formats::{
+        ImportMode, ImportReport,
+        json::{
+            Backup, BoardRecord, ChecklistRecord, ColumnRecord, EventRecord, SCHEMA_VERSION,
+            TaskRecord, TimeEntryRecord,
+        },
+    },
     migrator::Migrator,
-    task::{Status, Task},
+    recurrence::Recurrence,
+    task::{Priority, Status, Task, same_tag},
+    time_entry::TimeEntry,
     utils::db_timestamp_to_local_dt,
 };
 use chrono::{DateTime, Local};
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use strum::{EnumMessage, IntoEnumIterator};
+
+/// Selects tasks with their tags folded into a single `tags` column, their
+/// checklist progress and unfinished blockers, so a board loads in one query
+/// instead of one per task.
+const TASKS_QUERY: &str = "SELECT t.*, GROUP_CONCAT(tg.name, char(31)) AS tags,
+COALESCE(cl.done, 0) AS checklist_done, COALESCE(cl.total, 0) AS checklist_total,
+(
+    SELECT COALESCE(SUM(unixepoch(te.ended_at) - unixepoch(te.started_at)), 0)
+    FROM time_entries te WHERE te.task_id = t.id AND te.ended_at IS NOT NULL
+) AS time_spent,
+(
+    SELECT te.started_at FROM time_entries te
+    WHERE te.task_id = t.id AND te.ended_at IS NULL
+) AS timer_started_at,
+(
+    SELECT GROUP_CONCAT(d.blocked_by_id) FROM task_dependencies d
+    JOIN tasks b ON b.id = d.blocked_by_id
+    WHERE d.task_id = t.id AND b.status NOT IN ('done', 'archived') AND b.deleted_at IS NULL
+) AS blocked_by
+FROM tasks t
+LEFT JOIN task_tags tt ON tt.task_id = t.id
+LEFT JOIN tags tg ON tg.id = tt.tag_id
+LEFT JOIN (
+    SELECT task_id, SUM(done) AS done, COUNT(*) AS total
+    FROM checklist_items GROUP BY task_id
+) cl ON cl.task_id = t.id";
 
 pub struct Database {
     connection: Connection,
+    /// Where tasks without an explicit `position` land in their column.
+    pub insert_position: InsertPosition,
 }
 
 impl Database {
@@ -21,81 +67,1554 @@ impl Database {
 
         let connection = Connection::open("./.rstd/data.db")?;
 
-        Ok(Database { connection })
+        Ok(Database {
+            connection,
+            insert_position: InsertPosition::Bottom,
+        })
     }
 
-    pub fn apply_migrations(&mut self) -> Result<(), ()> {
-        let migrations_str = Migrator::new().get_migrations();
+    pub fn apply_migrations(&mut self) -> Result<(), AppError> {
+        self.apply_migrations_from(&Migrator::new())
+    }
 
-        self.connection
-            .execute_batch(&migrations_str)
-            .expect("Failed to apply migrations");
+    /// An empty database in memory with every migration of the source tree
+    /// applied.
+    #[cfg(test)]
+    pub fn in_memory() -> Database {
+        let mut db = Database {
+            connection: Connection::open_in_memory().unwrap(),
+            insert_position: InsertPosition::Bottom,
+        };
+        let migrations_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+        db.apply_migrations_from(&Migrator::in_dir(migrations_dir))
+            .unwrap();
+        db
+    }
+
+    fn apply_migrations_from(&mut self, migrator: &Migrator) -> Result<(), AppError> {
+        self.connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+    name TEXT PRIMARY KEY,
+    applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);",
+        )?;
+
+        for (name, sql) in migrator.get_migrations() {
+            let applied: bool = self.connection.query_row(
+                "SELECT EXISTS(SELECT 1 FROM schema_migrations WHERE name = ?1)",
+                params![name],
+                |row| row.get(0),
+            )?;
+            if applied {
+                continue;
+            }
+
+            let tx = self.connection.savepoint()?;
+            tx.execute_batch(&sql)?;
+            tx.execute(
+                "INSERT INTO schema_migrations (name) VALUES (?1)",
+                params![name],
+            )?;
+            tx.commit()?;
+        }
+
+        // Enabled only after migrating: `ALTER TABLE ... REFERENCES` needs it off.
+        self.connection.execute_batch("PRAGMA foreign_keys = ON;")?;
 
         Ok(())
     }
 
-    pub fn load_tasks(&self) -> Result<Vec<Task>, AppError> {
-        let mut stmt = self.connection.prepare("SELECT * FROM tasks")?;
-        let task_iter = stmt.query_map([], |row| {
-            let status_str: String = row.get("status")?;
+    /// Runs `f` as one transaction: either every change it makes is kept or,
+    /// when it fails, none is. Transactions nest, as do the ones of the
+    /// methods `f` calls.
+    pub fn transaction<T>(
+        &mut self,
+        f: impl FnOnce(&mut Database) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        self.savepoint(true, f)
+    }
+
+    /// Runs `f` like `transaction`, then undoes whatever it changed, so a dry
+    /// run sees the same ids and boards as the real one.
+    pub fn dry_run<T>(
+        &mut self,
+        f: impl FnOnce(&mut Database) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        self.savepoint(false, f)
+    }
 
+    fn savepoint<T>(
+        &mut self,
+        keep: bool,
+        f: impl FnOnce(&mut Database) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        self.connection.execute_batch("SAVEPOINT batch")?;
+        let result = f(self);
+        if keep && result.is_ok() {
+            self.connection.execute_batch("RELEASE batch")?;
+        } else {
+            self.connection
+                .execute_batch("ROLLBACK TO batch; RELEASE batch")?;
+        }
+        result
+    }
+
+    pub fn load_boards(&self) -> Result<Vec<Board>, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT * FROM boards ORDER BY id")?;
+        let board_iter = stmt.query_map([], |row| {
             let created_at_str: String = row.get("created_at")?;
 
-            let created_at = db_timestamp_to_local_dt(&created_at_str);
+            Ok(Board {
+                id: row.get("id")?,
+                name: row.get("name")?,
+                created_at: db_timestamp_to_local_dt(&created_at_str),
+            })
+        })?;
+        let boards: Result<Vec<Board>, rusqlite::Error> = board_iter.collect();
+        Ok(boards?)
+    }
 
-            let updated_at_str: String = row.get("updated_at")?;
-            let updated_at = db_timestamp_to_local_dt(&updated_at_str);
+    /// Inserts the board along with the default five-column workflow.
+    pub fn insert_board(&mut self, b: &Board) -> Result<i64, AppError> {
+        let tx = self.connection.savepoint()?;
+        tx.execute(
+            "INSERT INTO boards (name, created_at) VALUES (?1, ?2);",
+            params![b.name, b.created_at.to_rfc3339()],
+        )?;
+        let id = tx.last_insert_rowid();
+        for (position, status) in Status::iter().enumerate() {
+            let column = Column::new(
+                id,
+                status.get_message().unwrap().to_string(),
+                position as i64,
+                status,
+            );
+            insert_column(&tx, &column)?;
+        }
+        tx.commit()?;
+        Ok(id)
+    }
 
-            let maybe_deadline_str: Option<String> = row.get("deadline")?;
-            let mut deadline: Option<DateTime<Local>> = None;
-            if let Some(d_str) = maybe_deadline_str {
-                deadline = Some(db_timestamp_to_local_dt(&d_str));
-            }
+    pub fn update_board(&mut self, b: &Board) -> Result<(), AppError> {
+        if let Some(id) = b.id {
+            self.connection.execute(
+                "UPDATE boards SET name = ?1 WHERE id = ?2",
+                params![b.name, id],
+            )?;
+        }
+        Ok(())
+    }
 
-            Ok(Task {
+    /// Deletes the board together with all of its tasks.
+    pub fn delete_board(&mut self, b: &Board) -> Result<(), AppError> {
+        if let Some(id) = b.id {
+            let tx = self.connection.savepoint()?;
+            tx.execute(
+                "INSERT INTO task_events (task_id, kind, from_value, created_at)
+SELECT id, ?1, description, ?2 FROM tasks WHERE board_id = ?3",
+                params![
+                    EventKind::Deleted.to_string(),
+                    Local::now().to_rfc3339(),
+                    id
+                ],
+            )?;
+            tx.execute("DELETE from boards WHERE id = ?1", params![id])?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    pub fn load_columns(&self, board_id: i64) -> Result<Vec<Column>, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT * FROM columns WHERE board_id = ?1 ORDER BY position, id")?;
+        let column_iter = stmt.query_map(params![board_id], |row| {
+            let status_str: String = row.get("status")?;
+
+            Ok(Column {
                 id: row.get("id")?,
-                description: row.get("description")?,
+                board_id: row.get("board_id")?,
+                name: row.get("name")?,
+                position: row.get("position")?,
+                color: row.get("color")?,
                 status: Status::from_str(&status_str)
                     .map_err(|_| rusqlite::Error::UnwindingPanic)?,
-                created_at: created_at,
-                updated_at: updated_at,
-                deadline: deadline,
+                wip_limit: row.get("wip_limit")?,
             })
         })?;
+        let columns: Result<Vec<Column>, rusqlite::Error> = column_iter.collect();
+        Ok(columns?)
+    }
+
+    pub fn insert_column(&mut self, c: &Column) -> Result<i64, AppError> {
+        let tx = self.connection.savepoint()?;
+        let id = insert_column(&tx, c)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Updates the column and keeps the status of its tasks in sync with it.
+    pub fn update_column(&mut self, c: &Column) -> Result<(), AppError> {
+        if let Some(id) = c.id {
+            let tx = self.connection.savepoint()?;
+            tx.execute(
+                "UPDATE columns SET name = ?1, position = ?2, color = ?3, status = ?4, wip_limit = ?5
+WHERE id = ?6",
+                params![
+                    c.name,
+                    c.position,
+                    c.color,
+                    c.status.to_string(),
+                    c.wip_limit,
+                    id
+                ],
+            )?;
+            tx.execute(
+                "INSERT INTO task_events (task_id, kind, from_value, to_value, created_at)
+SELECT id, ?1, status, ?2, ?3 FROM tasks WHERE column_id = ?4 AND status != ?2",
+                params![
+                    EventKind::StatusChanged.to_string(),
+                    c.status.to_string(),
+                    Local::now().to_rfc3339(),
+                    id
+                ],
+            )?;
+            tx.execute(
+                "UPDATE tasks SET status = ?1 WHERE column_id = ?2",
+                params![c.status.to_string(), id],
+            )?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Deletes a column. Tasks of it in the trash lose their column and are
+    /// placed by status when restored.
+    pub fn delete_column(&mut self, c: &Column) -> Result<(), AppError> {
+        if let Some(id) = c.id {
+            let tx = self.connection.savepoint()?;
+            tx.execute(
+                "UPDATE tasks SET column_id = NULL WHERE column_id = ?1",
+                params![id],
+            )?;
+            tx.execute("DELETE from columns WHERE id = ?1", params![id])?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    pub fn load_tasks(&self, board_id: i64) -> Result<Vec<Task>, AppError> {
+        let mut stmt = self.connection.prepare(&format!(
+            "{TASKS_QUERY} WHERE t.board_id = ?1 AND t.deleted_at IS NULL GROUP BY t.id"
+        ))?;
+        let task_iter = stmt.query_map(params![board_id], task_from_row)?;
         let tasks: Result<Vec<Task>, rusqlite::Error> = task_iter.collect();
         Ok(tasks?)
     }
 
-    pub fn insert_task(&mut self, t: &Task) -> Result<(), AppError> {
-        self.connection.execute(
-            "INSERT INTO tasks (description, status, created_at, updated_at, deadline)
-VALUES (?1, ?2, ?3, ?4, ?5);",
+    /// Tasks of every board outside the trash, board by board.
+    pub fn load_all_tasks(&self) -> Result<Vec<Task>, AppError> {
+        let mut stmt = self.connection.prepare(&format!(
+            "{TASKS_QUERY} WHERE t.deleted_at IS NULL GROUP BY t.id ORDER BY t.board_id, t.id"
+        ))?;
+        let task_iter = stmt.query_map([], task_from_row)?;
+        let tasks: Result<Vec<Task>, rusqlite::Error> = task_iter.collect();
+        Ok(tasks?)
+    }
+
+    /// Names of every tag attached to at least one task on the board.
+    pub fn load_tags(&self, board_id: i64) -> Result<Vec<String>, AppError> {
+        let mut stmt = self.connection.prepare(
+            "SELECT DISTINCT tg.name FROM tags tg
+JOIN task_tags tt ON tt.tag_id = tg.id
+JOIN tasks t ON t.id = tt.task_id
+WHERE t.board_id = ?1 AND t.deleted_at IS NULL ORDER BY tg.name",
+        )?;
+        let tags: Result<Vec<String>, rusqlite::Error> = stmt
+            .query_map(params![board_id], |row| row.get(0))?
+            .collect();
+        Ok(tags?)
+    }
+
+    pub fn load_checklist(&self, task_id: i64) -> Result<Vec<ChecklistItem>, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT * FROM checklist_items WHERE task_id = ?1 ORDER BY id")?;
+        let item_iter = stmt.query_map(params![task_id], |row| {
+            let created_at_str: String = row.get("created_at")?;
+
+            Ok(ChecklistItem {
+                id: row.get("id")?,
+                task_id: row.get("task_id")?,
+                description: row.get("description")?,
+                done: row.get("done")?,
+                created_at: db_timestamp_to_local_dt(&created_at_str),
+            })
+        })?;
+        let items: Result<Vec<ChecklistItem>, rusqlite::Error> = item_iter.collect();
+        Ok(items?)
+    }
+
+    pub fn insert_checklist_item(&mut self, item: &ChecklistItem) -> Result<i64, AppError> {
+        let tx = self.connection.savepoint()?;
+        tx.execute(
+            "INSERT INTO checklist_items (task_id, description, done, created_at)
+VALUES (?1, ?2, ?3, ?4);",
+            params![
+                item.task_id,
+                item.description,
+                item.done,
+                item.created_at.to_rfc3339(),
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        log_event(
+            &tx,
+            item.task_id,
+            EventKind::ChecklistChanged,
+            None,
+            Some(item.to_string()),
+        )?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    pub fn update_checklist_item(&mut self, item: &ChecklistItem) -> Result<(), AppError> {
+        if let Some(id) = item.id {
+            let tx = self.connection.savepoint()?;
+            let old = tx.query_row(
+                "SELECT description, done FROM checklist_items WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok(ChecklistItem {
+                        description: row.get(0)?,
+                        done: row.get(1)?,
+                        ..item.clone()
+                    })
+                },
+            )?;
+            tx.execute(
+                "UPDATE checklist_items SET description = ?1, done = ?2 WHERE id = ?3",
+                params![item.description, item.done, id],
+            )?;
+            if old.to_string() != item.to_string() {
+                log_event(
+                    &tx,
+                    item.task_id,
+                    EventKind::ChecklistChanged,
+                    Some(old.to_string()),
+                    Some(item.to_string()),
+                )?;
+            }
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_checklist_item(&mut self, item: &ChecklistItem) -> Result<(), AppError> {
+        if let Some(id) = item.id {
+            let tx = self.connection.savepoint()?;
+            tx.execute("DELETE from checklist_items WHERE id = ?1", params![id])?;
+            log_event(
+                &tx,
+                item.task_id,
+                EventKind::ChecklistChanged,
+                Some(item.to_string()),
+                None,
+            )?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Ids of every task that blocks the given one, finished or not.
+    pub fn load_dependencies(&self, task_id: i64) -> Result<Vec<i64>, AppError> {
+        let mut stmt = self.connection.prepare(
+            "SELECT blocked_by_id FROM task_dependencies WHERE task_id = ?1 ORDER BY blocked_by_id",
+        )?;
+        let ids: Result<Vec<i64>, rusqlite::Error> = stmt
+            .query_map(params![task_id], |row| row.get(0))?
+            .collect();
+        Ok(ids?)
+    }
+
+    /// Records that `task_id` is blocked by `blocked_by_id`, rejecting links
+    /// that would make a task (transitively) block itself.
+    pub fn add_dependency(&mut self, task_id: i64, blocked_by_id: i64) -> Result<(), AppError> {
+        let creates_cycle: bool = task_id == blocked_by_id
+            || self.connection.query_row(
+                "WITH RECURSIVE blockers(id) AS (
+    SELECT blocked_by_id FROM task_dependencies WHERE task_id = ?1
+    UNION
+    SELECT d.blocked_by_id FROM task_dependencies d JOIN blockers ON d.task_id = blockers.id
+)
+SELECT EXISTS(SELECT 1 FROM blockers WHERE id = ?2)",
+                params![blocked_by_id, task_id],
+                |row| row.get(0),
+            )?;
+        if creates_cycle {
+            return Err(AppError::DependencyCycle {
+                task_id,
+                blocked_by_id,
+            });
+        }
+
+        let tx = self.connection.savepoint()?;
+        let added = tx.execute(
+            "INSERT OR IGNORE INTO task_dependencies (task_id, blocked_by_id) VALUES (?1, ?2)",
+            params![task_id, blocked_by_id],
+        )?;
+        if added > 0 {
+            log_event(
+                &tx,
+                task_id,
+                EventKind::BlockerAdded,
+                None,
+                Some(format!("#{blocked_by_id}")),
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn remove_dependency(&mut self, task_id: i64, blocked_by_id: i64) -> Result<(), AppError> {
+        let tx = self.connection.savepoint()?;
+        let removed = tx.execute(
+            "DELETE FROM task_dependencies WHERE task_id = ?1 AND blocked_by_id = ?2",
+            params![task_id, blocked_by_id],
+        )?;
+        if removed > 0 {
+            log_event(
+                &tx,
+                task_id,
+                EventKind::BlockerRemoved,
+                Some(format!("#{blocked_by_id}")),
+                None,
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Starts a timer on the task unless one is already running, and returns
+    /// the id of the time entry it started.
+    pub fn start_timer(&mut self, task_id: i64) -> Result<Option<i64>, AppError> {
+        let now = Local::now().to_rfc3339();
+        let tx = self.connection.savepoint()?;
+        let started = tx.execute(
+            "INSERT INTO time_entries (task_id, started_at)
+SELECT ?1, ?2 WHERE NOT EXISTS (
+    SELECT 1 FROM time_entries WHERE task_id = ?1 AND ended_at IS NULL
+)",
+            params![task_id, now],
+        )?;
+        let entry_id = (started > 0).then(|| tx.last_insert_rowid());
+        if entry_id.is_some() {
+            log_event(&tx, task_id, EventKind::TimerStarted, None, Some(now))?;
+        }
+        tx.commit()?;
+        Ok(entry_id)
+    }
+
+    /// Stops the running timer of the task, if any.
+    pub fn stop_timer(&mut self, task_id: i64) -> Result<(), AppError> {
+        let now = Local::now().to_rfc3339();
+        let tx = self.connection.savepoint()?;
+        let stopped = tx.execute(
+            "UPDATE time_entries SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
+            params![now, task_id],
+        )?;
+        if stopped > 0 {
+            log_event(&tx, task_id, EventKind::TimerStopped, None, Some(now))?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Time entries of a task, most recent first.
+    pub fn load_time_entries(&self, task_id: i64) -> Result<Vec<TimeEntry>, AppError> {
+        let mut stmt = self.connection.prepare(
+            "SELECT * FROM time_entries WHERE task_id = ?1 ORDER BY started_at DESC, id DESC",
+        )?;
+        let entry_iter = stmt.query_map(params![task_id], time_entry_from_row)?;
+        let entries: Result<Vec<TimeEntry>, rusqlite::Error> = entry_iter.collect();
+        Ok(entries?)
+    }
+
+    /// Time entries of every task on the board with the task description.
+    pub fn load_board_time_entries(
+        &self,
+        board_id: i64,
+    ) -> Result<Vec<(TimeEntry, String)>, AppError> {
+        let mut stmt = self.connection.prepare(
+            "SELECT te.*, t.description FROM time_entries te
+JOIN tasks t ON t.id = te.task_id
+WHERE t.board_id = ?1 AND t.deleted_at IS NULL ORDER BY te.started_at",
+        )?;
+        let entry_iter = stmt.query_map(params![board_id], |row| {
+            Ok((time_entry_from_row(row)?, row.get("description")?))
+        })?;
+        let entries: Result<Vec<(TimeEntry, String)>, rusqlite::Error> = entry_iter.collect();
+        Ok(entries?)
+    }
+
+    pub fn update_time_entry(&mut self, entry: &TimeEntry) -> Result<(), AppError> {
+        if let Some(id) = entry.id {
+            let tx = self.connection.savepoint()?;
+            let old = tx.query_row(
+                "SELECT * FROM time_entries WHERE id = ?1",
+                params![id],
+                time_entry_from_row,
+            )?;
+            tx.execute(
+                "UPDATE time_entries SET started_at = ?1, ended_at = ?2 WHERE id = ?3",
+                params![
+                    entry.started_at.to_rfc3339(),
+                    entry.ended_at.map(|dt| dt.to_rfc3339()),
+                    id
+                ],
+            )?;
+            let (from, to) = (time_entry_interval(&old), time_entry_interval(entry));
+            if from != to {
+                log_event(
+                    &tx,
+                    old.task_id,
+                    EventKind::TimeEntryChanged,
+                    Some(from),
+                    Some(to),
+                )?;
+            }
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_time_entry(&mut self, entry_id: i64) -> Result<(), AppError> {
+        let tx = self.connection.savepoint()?;
+        let old = tx.query_row(
+            "SELECT * FROM time_entries WHERE id = ?1",
+            params![entry_id],
+            time_entry_from_row,
+        )?;
+        tx.execute("DELETE from time_entries WHERE id = ?1", params![entry_id])?;
+        log_event(
+            &tx,
+            old.task_id,
+            EventKind::TimeEntryDeleted,
+            Some(time_entry_interval(&old)),
+            None,
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Activity log of a task, oldest first.
+    pub fn load_events(&self, task_id: i64) -> Result<Vec<TaskEvent>, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT * FROM task_events WHERE task_id = ?1 ORDER BY created_at, id")?;
+        let event_iter = stmt.query_map(params![task_id], |row| {
+            let kind_str: String = row.get("kind")?;
+            let created_at_str: String = row.get("created_at")?;
+
+            Ok(TaskEvent {
+                kind: EventKind::from_str(&kind_str)
+                    .map_err(|_| rusqlite::Error::UnwindingPanic)?,
+                from: row.get("from_value")?,
+                to: row.get("to_value")?,
+                created_at: db_timestamp_to_local_dt(&created_at_str),
+            })
+        })?;
+        let events: Result<Vec<TaskEvent>, rusqlite::Error> = event_iter.collect();
+        Ok(events?)
+    }
+
+    /// A single task, unless it is in the trash.
+    pub fn load_task(&self, task_id: i64) -> Result<Task, AppError> {
+        self.connection
+            .query_row(
+                &format!("{TASKS_QUERY} WHERE t.id = ?1 AND t.deleted_at IS NULL GROUP BY t.id"),
+                params![task_id],
+                task_from_row,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => AppError::MissingTask(task_id),
+                e => e.into(),
+            })
+    }
+
+    pub fn insert_task(&mut self, t: &Task) -> Result<i64, AppError> {
+        let tx = self.connection.savepoint()?;
+        let (column_id, status) = resolve_column(&tx, t)?;
+        let position = match t.position {
+            Some(position) => position,
+            None => edge_position(&tx, column_id, self.insert_position)?,
+        };
+        tx.execute(
+            "INSERT INTO tasks (description, status, created_at, updated_at, deadline, board_id,
+priority, recurrence, column_id, position, ordered_manually)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11);",
             params![
                 t.description,
-                t.status.to_string(),
+                status.to_string(),
                 t.created_at.to_rfc3339(),
                 t.updated_at.to_rfc3339(),
                 t.deadline.as_ref().map(|d| d.to_rfc3339()),
+                t.board_id,
+                t.priority.to_string(),
+                t.recurrence.as_ref().map(|r| r.to_string()),
+                column_id,
+                position,
+                t.ordered_manually && t.position.is_some(),
             ],
         )?;
-        Ok(())
+        let id = tx.last_insert_rowid();
+        save_tags(&tx, id, &t.tags)?;
+        log_event(
+            &tx,
+            id,
+            EventKind::Created,
+            None,
+            Some(t.description.to_string()),
+        )?;
+        tx.commit()?;
+        Ok(id)
     }
 
     pub fn update_task(&mut self, t: &Task) -> Result<(), AppError> {
+        self.update_task_at(t, Local::now())
+    }
+
+    /// Like `update_task`, but with the given modification time, e.g. the
+    /// one a task had before an edit that is undone.
+    pub fn update_task_at(
+        &mut self,
+        t: &Task,
+        updated_at: DateTime<Local>,
+    ) -> Result<(), AppError> {
         if let Some(id) = t.id {
-            self.connection.execute(
-                "UPDATE tasks SET updated_at = ?1, status = ?2 WHERE id = ?3",
-                params![Local::now().to_rfc3339(), t.status.to_string(), id,],
+            let tx = self.connection.savepoint()?;
+            let old = task_by_id(&tx, id)?;
+            let (column_id, status) = resolve_column(&tx, t)?;
+            let position = match t.position {
+                Some(position) => position,
+                None => edge_position(&tx, column_id, self.insert_position)?,
+            };
+            tx.execute(
+                "UPDATE tasks SET updated_at = ?1, status = ?2, description = ?3, deadline = ?4,
+board_id = ?5, priority = ?6, recurrence = ?7, column_id = ?8, position = ?9,
+ordered_manually = ?10 WHERE id = ?11",
+                params![
+                    updated_at.to_rfc3339(),
+                    status.to_string(),
+                    t.description,
+                    t.deadline.as_ref().map(|d| d.to_rfc3339()),
+                    t.board_id,
+                    t.priority.to_string(),
+                    t.recurrence.as_ref().map(|r| r.to_string()),
+                    column_id,
+                    position,
+                    // A card placed at the edge of a column is no longer
+                    // where the user put it.
+                    t.ordered_manually && t.position.is_some(),
+                    id,
+                ],
+            )?;
+            save_tags(&tx, id, &t.tags)?;
+            log_task_changes(&tx, &old, &task_by_id(&tx, id)?)?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Overrides when the task last changed, for imports that know when the
+    /// change was made in another app.
+    pub fn set_task_updated_at(
+        &mut self,
+        task_id: i64,
+        updated_at: DateTime<Local>,
+    ) -> Result<(), AppError> {
+        let tx = self.connection.savepoint()?;
+        let old: String = tx.query_row(
+            "SELECT updated_at FROM tasks WHERE id = ?1",
+            params![task_id],
+            |row| row.get(0),
+        )?;
+        let new = updated_at.to_rfc3339();
+        tx.execute(
+            "UPDATE tasks SET updated_at = ?1 WHERE id = ?2",
+            params![new, task_id],
+        )?;
+        if old != new {
+            log_event(
+                &tx,
+                task_id,
+                EventKind::UpdatedAtChanged,
+                Some(old),
+                Some(new),
             )?;
         }
+        tx.commit()?;
         Ok(())
     }
 
+    /// Moves the task to the trash and stops its timer.
     pub fn delete_task(&mut self, t: &Task) -> Result<(), AppError> {
         if let Some(id) = t.id {
-            self.connection
-                .execute("DELETE from tasks WHERE id = ?1", params![id])?;
+            let now = Local::now().to_rfc3339();
+            let tx = self.connection.savepoint()?;
+            tx.execute(
+                "UPDATE tasks SET deleted_at = ?1 WHERE id = ?2",
+                params![now, id],
+            )?;
+            tx.execute(
+                "UPDATE time_entries SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
+                params![now, id],
+            )?;
+            log_event(
+                &tx,
+                id,
+                EventKind::Deleted,
+                Some(t.description.to_string()),
+                None,
+            )?;
+            tx.commit()?;
         }
         Ok(())
     }
+
+    /// Takes a task out of the trash. It goes back to its column, or to the
+    /// first column with its status when that column was deleted meanwhile.
+    pub fn restore_task(&mut self, task_id: i64) -> Result<(), AppError> {
+        let tx = self.connection.savepoint()?;
+        let t = task_by_id(&tx, task_id).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::MissingTask(task_id),
+            e => e.into(),
+        })?;
+        let (column_id, status) = resolve_column(&tx, &t)?;
+        let (position, ordered_manually) = match t.position {
+            Some(position) if t.column_id == Some(column_id) => (position, t.ordered_manually),
+            _ => (edge_position(&tx, column_id, self.insert_position)?, false),
+        };
+        tx.execute(
+            "UPDATE tasks SET deleted_at = NULL, column_id = ?1, status = ?2, position = ?3,
+ordered_manually = ?4 WHERE id = ?5",
+            params![
+                column_id,
+                status.to_string(),
+                position,
+                ordered_manually,
+                task_id
+            ],
+        )?;
+        log_event(
+            &tx,
+            task_id,
+            EventKind::Restored,
+            None,
+            Some(t.description.to_string()),
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Tasks in `status` since before `before`, on boards that have a column
+    /// with the `target` status to move them to. The time a task entered its
+    /// status comes from the activity log, or its last update without one.
+    pub fn load_stale_tasks(
+        &self,
+        status: Status,
+        before: DateTime<Local>,
+        target: Status,
+    ) -> Result<Vec<Task>, AppError> {
+        let mut stmt = self.connection.prepare(&format!(
+            "{TASKS_QUERY} WHERE t.deleted_at IS NULL AND t.status = ?1
+AND COALESCE((
+    SELECT MAX(unixepoch(e.created_at)) FROM task_events e
+    WHERE e.task_id = t.id AND e.kind = ?2 AND e.to_value = ?1
+), unixepoch(t.updated_at)) < unixepoch(?3)
+AND EXISTS (SELECT 1 FROM columns c WHERE c.board_id = t.board_id AND c.status = ?4)
+GROUP BY t.id"
+        ))?;
+        let task_iter = stmt.query_map(
+            params![
+                status.to_string(),
+                EventKind::StatusChanged.to_string(),
+                before.to_rfc3339(),
+                target.to_string()
+            ],
+            task_from_row,
+        )?;
+        let tasks: Result<Vec<Task>, rusqlite::Error> = task_iter.collect();
+        Ok(tasks?)
+    }
+
+    /// Every board, column and task, the trash included, for a JSON backup.
+    pub fn export_backup(&self) -> Result<Backup, AppError> {
+        let mut boards = Vec::new();
+        for board in self.load_boards()? {
+            let id = board.id.unwrap_or_default();
+            let columns = self
+                .load_columns(id)?
+                .into_iter()
+                .map(|c| ColumnRecord {
+                    id: c.id.unwrap_or_default(),
+                    name: c.name,
+                    position: c.position,
+                    color: c.color,
+                    status: c.status,
+                    wip_limit: c.wip_limit,
+                })
+                .collect();
+            boards.push(BoardRecord {
+                id,
+                name: board.name,
+                created_at: board.created_at,
+                columns,
+            });
+        }
+
+        let mut stmt = self
+            .connection
+            .prepare(&format!("{TASKS_QUERY} GROUP BY t.id ORDER BY t.id"))?;
+        let tasks: Result<Vec<Task>, rusqlite::Error> =
+            stmt.query_map([], task_from_row)?.collect();
+        let mut records = Vec::new();
+        for t in tasks? {
+            let id = t.id.unwrap_or_default();
+            let checklist = self
+                .load_checklist(id)?
+                .into_iter()
+                .map(|item| ChecklistRecord {
+                    description: item.description,
+                    done: item.done,
+                    created_at: item.created_at,
+                })
+                .collect();
+            // Oldest first, the order they were tracked in.
+            let time_entries = self
+                .load_time_entries(id)?
+                .into_iter()
+                .rev()
+                .map(|entry| TimeEntryRecord {
+                    started_at: entry.started_at,
+                    ended_at: entry.ended_at,
+                })
+                .collect();
+            let events = self
+                .load_events(id)?
+                .into_iter()
+                .map(|event| EventRecord {
+                    kind: event.kind.to_string(),
+                    from: event.from,
+                    to: event.to,
+                    created_at: event.created_at,
+                })
+                .collect();
+            records.push(TaskRecord {
+                id,
+                board_id: t.board_id,
+                column_id: t.column_id,
+                status: t.status,
+                position: t.position,
+                ordered_manually: t.ordered_manually,
+                priority: t.priority,
+                description: t.description,
+                created_at: t.created_at,
+                updated_at: t.updated_at,
+                deadline: t.deadline,
+                deleted_at: t.deleted_at,
+                tags: t.tags,
+                recurrence: t.recurrence.map(|r| r.to_string()),
+                blocked_by: self.load_dependencies(id)?,
+                checklist,
+                time_entries,
+                events,
+            });
+        }
+
+        Ok(Backup {
+            schema_version: SCHEMA_VERSION,
+            exported_at: Local::now(),
+            boards,
+            tasks: records,
+        })
+    }
+
+    /// Loads a JSON backup in one transaction. Boards are matched by name and
+    /// columns by name within them; tasks keep their ids, and a task whose id
+    /// is already taken is left out and reported as a conflict.
+    pub fn import_backup(
+        &mut self,
+        backup: &Backup,
+        mode: ImportMode,
+        dry_run: bool,
+    ) -> Result<ImportReport, AppError> {
+        if backup.schema_version > SCHEMA_VERSION {
+            return Err(AppError::InvalidImport(format!(
+                "schema version {} is newer than the supported {SCHEMA_VERSION}",
+                backup.schema_version
+            )));
+        }
+        if mode == ImportMode::Replace && backup.boards.is_empty() {
+            return Err(AppError::InvalidImport(
+                "the file has no boards to replace the database with".to_string(),
+            ));
+        }
+        let tx = self.connection.savepoint()?;
+        // The activity log stays: it outlives tasks, and a file without one
+        // would otherwise wipe it.
+        if mode == ImportMode::Replace {
+            tx.execute_batch("DELETE FROM tasks; DELETE FROM boards; DELETE FROM tags;")?;
+        }
+        let mut report = ImportReport::default();
+
+        let mut board_ids = HashMap::new();
+        let mut column_ids = HashMap::new();
+        for board in &backup.boards {
+            let existing: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM boards WHERE name = ?1",
+                    params![board.name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let board_id = match existing {
+                Some(id) => id,
+                None => {
+                    tx.execute(
+                        "INSERT INTO boards (id, name, created_at) VALUES (?1, ?2, ?3)",
+                        params![
+                            free_id(&tx, "boards", board.id)?,
+                            board.name,
+                            board.created_at.to_rfc3339()
+                        ],
+                    )?;
+                    report.boards += 1;
+                    tx.last_insert_rowid()
+                }
+            };
+            board_ids.insert(board.id, board_id);
+
+            for column in &board.columns {
+                let existing: Option<i64> = tx
+                    .query_row(
+                        "SELECT id FROM columns WHERE board_id = ?1 AND name = ?2",
+                        params![board_id, column.name],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                let column_id = match existing {
+                    Some(id) => id,
+                    None => {
+                        tx.execute(
+                            "INSERT INTO columns (id, board_id, name, position, color, status,
+wip_limit) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                            params![
+                                free_id(&tx, "columns", column.id)?,
+                                board_id,
+                                column.name,
+                                column.position,
+                                column.color,
+                                column.status.to_string(),
+                                column.wip_limit
+                            ],
+                        )?;
+                        tx.last_insert_rowid()
+                    }
+                };
+                column_ids.insert(column.id, column_id);
+            }
+        }
+
+        let mut imported = HashSet::new();
+        for record in &backup.tasks {
+            if free_id(&tx, "tasks", record.id)?.is_none() {
+                report.skipped.push(format!(
+                    "task #{} \"{}\": id already in use",
+                    record.id,
+                    record.description.lines().next().unwrap_or_default()
+                ));
+                continue;
+            }
+            let Some(&board_id) = board_ids.get(&record.board_id) else {
+                report.skipped.push(format!(
+                    "task #{}: board #{} is not in the file",
+                    record.id, record.board_id
+                ));
+                continue;
+            };
+            let mut task = Task::new(
+                record.description.to_string(),
+                Some(record.status),
+                record.deadline,
+                board_id,
+            );
+            task.column_id = record.column_id.and_then(|id| column_ids.get(&id).copied());
+            let (column_id, status) = resolve_column(&tx, &task)?;
+            let position = match record.position {
+                Some(position) => position,
+                None => edge_position(&tx, column_id, self.insert_position)?,
+            };
+            let recurrence = record
+                .recurrence
+                .as_ref()
+                .and_then(|r| Recurrence::from_str(r).ok());
+            tx.execute(
+                "INSERT INTO tasks (id, description, status, created_at, updated_at, deadline,
+board_id, priority, recurrence, column_id, position, ordered_manually, deleted_at)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![
+                    record.id,
+                    record.description,
+                    status.to_string(),
+                    record.created_at.to_rfc3339(),
+                    record.updated_at.to_rfc3339(),
+                    record.deadline.as_ref().map(|d| d.to_rfc3339()),
+                    board_id,
+                    record.priority.to_string(),
+                    recurrence.map(|r| r.to_string()),
+                    column_id,
+                    position,
+                    record.ordered_manually && record.position.is_some(),
+                    record.deleted_at.as_ref().map(|d| d.to_rfc3339()),
+                ],
+            )?;
+            save_tags(&tx, record.id, &record.tags)?;
+            for item in &record.checklist {
+                tx.execute(
+                    "INSERT INTO checklist_items (task_id, description, done, created_at)
+VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        record.id,
+                        item.description,
+                        item.done,
+                        item.created_at.to_rfc3339()
+                    ],
+                )?;
+            }
+            for entry in &record.time_entries {
+                tx.execute(
+                    "INSERT INTO time_entries (task_id, started_at, ended_at) VALUES (?1, ?2, ?3)",
+                    params![
+                        record.id,
+                        entry.started_at.to_rfc3339(),
+                        entry.ended_at.as_ref().map(|d| d.to_rfc3339())
+                    ],
+                )?;
+            }
+            import_events(&tx, record)?;
+            imported.insert(record.id);
+            report.tasks += 1;
+        }
+
+        for record in backup.tasks.iter().filter(|t| imported.contains(&t.id)) {
+            for blocked_by_id in record.blocked_by.iter().filter(|id| imported.contains(id)) {
+                tx.execute(
+                    "INSERT OR IGNORE INTO task_dependencies (task_id, blocked_by_id)
+VALUES (?1, ?2)",
+                    params![record.id, blocked_by_id],
+                )?;
+            }
+        }
+        // A dry run rolls everything back once it is known to succeed.
+        if !dry_run {
+            tx.commit()?;
+        }
+        Ok(report)
+    }
+
+    /// When `path` was last synced and what rstd wrote to it then.
+    pub fn load_sync_state(
+        &self,
+        path: &str,
+    ) -> Result<Option<(DateTime<Local>, String)>, AppError> {
+        let state = self
+            .connection
+            .query_row(
+                "SELECT synced_at, contents FROM sync_state WHERE path = ?1",
+                params![path],
+                |row| {
+                    let synced_at: String = row.get(0)?;
+                    Ok((db_timestamp_to_local_dt(&synced_at), row.get(1)?))
+                },
+            )
+            .optional()?;
+        Ok(state)
+    }
+
+    pub fn save_sync_state(
+        &mut self,
+        path: &str,
+        synced_at: DateTime<Local>,
+        contents: &str,
+    ) -> Result<(), AppError> {
+        self.connection.execute(
+            "INSERT INTO sync_state (path, synced_at, contents) VALUES (?1, ?2, ?3)
+ON CONFLICT (path) DO UPDATE SET synced_at = excluded.synced_at, contents = excluded.contents",
+            params![path, synced_at.to_rfc3339(), contents],
+        )?;
+        Ok(())
+    }
+
+    /// Random UUID of this database, made when it was created.
+    pub fn database_id(&self) -> Result<String, AppError> {
+        let uuid = self.connection.query_row(
+            "SELECT uuid FROM database_info WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(uuid)
+    }
+
+    /// The task another app knows as `external_id`.
+    pub fn external_task_id(
+        &self,
+        source: &str,
+        external_id: &str,
+    ) -> Result<Option<i64>, AppError> {
+        let id = self
+            .connection
+            .query_row(
+                "SELECT task_id FROM external_ids WHERE source = ?1 AND external_id = ?2",
+                params![source, external_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(id)
+    }
+
+    /// Ids `source` gave to tasks, by task id.
+    pub fn load_external_ids(&self, source: &str) -> Result<HashMap<i64, String>, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT task_id, external_id FROM external_ids WHERE source = ?1")?;
+        let ids = stmt
+            .query_map(params![source], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<i64, String>, rusqlite::Error>>()?;
+        Ok(ids)
+    }
+
+    pub fn save_external_id(
+        &mut self,
+        source: &str,
+        external_id: &str,
+        task_id: i64,
+    ) -> Result<(), AppError> {
+        self.connection.execute(
+            "INSERT INTO external_ids (source, external_id, task_id) VALUES (?1, ?2, ?3)
+ON CONFLICT (source, external_id) DO UPDATE SET task_id = excluded.task_id",
+            params![source, external_id, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Tasks of the board in the trash, most recently deleted first.
+    pub fn load_trash(&self, board_id: i64) -> Result<Vec<Task>, AppError> {
+        let mut stmt = self.connection.prepare(&format!(
+            "{TASKS_QUERY} WHERE t.board_id = ?1 AND t.deleted_at IS NOT NULL
+GROUP BY t.id ORDER BY t.deleted_at DESC"
+        ))?;
+        let task_iter = stmt.query_map(params![board_id], task_from_row)?;
+        let tasks: Result<Vec<Task>, rusqlite::Error> = task_iter.collect();
+        Ok(tasks?)
+    }
+
+    /// Deletes a task for good, together with its checklist and time entries.
+    pub fn purge_task(&mut self, t: &Task) -> Result<(), AppError> {
+        if let Some(id) = t.id {
+            let tx = self.connection.savepoint()?;
+            tx.execute("DELETE from tasks WHERE id = ?1", params![id])?;
+            log_event(
+                &tx,
+                id,
+                EventKind::Purged,
+                Some(t.description.to_string()),
+                None,
+            )?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Purges every task that has been in the trash since before `deleted_before`
+    /// and returns how many there were.
+    pub fn purge_trash(&mut self, deleted_before: DateTime<Local>) -> Result<usize, AppError> {
+        let tx = self.connection.savepoint()?;
+        tx.execute(
+            "INSERT INTO task_events (task_id, kind, from_value, created_at)
+SELECT id, ?1, description, ?2 FROM tasks
+WHERE deleted_at IS NOT NULL AND unixepoch(deleted_at) < unixepoch(?3)",
+            params![
+                EventKind::Purged.to_string(),
+                Local::now().to_rfc3339(),
+                deleted_before.to_rfc3339()
+            ],
+        )?;
+        let purged = tx.execute(
+            "DELETE FROM tasks WHERE deleted_at IS NOT NULL AND unixepoch(deleted_at) < unixepoch(?1)",
+            params![deleted_before.to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(purged)
+    }
+}
+
+fn task_by_id(conn: &Connection, id: i64) -> Result<Task, rusqlite::Error> {
+    conn.query_row(
+        &format!("{TASKS_QUERY} WHERE t.id = ?1 GROUP BY t.id"),
+        params![id],
+        task_from_row,
+    )
+}
+
+fn log_event(
+    conn: &Connection,
+    task_id: i64,
+    kind: EventKind,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO task_events (task_id, kind, from_value, to_value, created_at)
+VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            task_id,
+            kind.to_string(),
+            from,
+            to,
+            Local::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Appends one event per field that differs between two versions of a task.
+fn log_task_changes(conn: &Connection, old: &Task, new: &Task) -> Result<(), rusqlite::Error> {
+    let Some(id) = new.id else {
+        return Ok(());
+    };
+    let column_name = |column_id: Option<i64>| -> Result<Option<String>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT name FROM columns WHERE id = ?1",
+            params![column_id],
+            |row| row.get(0),
+        )
+        .or(Ok(None))
+    };
+    let deadline = |t: &Task| t.deadline.map(|d| d.to_rfc3339());
+    let recurrence = |t: &Task| t.recurrence.as_ref().map(|r| r.to_string());
+
+    let mut changes: Vec<(EventKind, Option<String>, Option<String>)> = Vec::new();
+    if old.status != new.status {
+        changes.push((
+            EventKind::StatusChanged,
+            Some(old.status.to_string()),
+            Some(new.status.to_string()),
+        ));
+    } else if old.column_id != new.column_id {
+        changes.push((
+            EventKind::ColumnChanged,
+            column_name(old.column_id)?,
+            column_name(new.column_id)?,
+        ));
+    } else if old.position != new.position {
+        changes.push((
+            EventKind::Reordered,
+            old.position.map(|p| p.to_string()),
+            new.position.map(|p| p.to_string()),
+        ));
+    }
+    if old.description != new.description {
+        changes.push((
+            EventKind::DescriptionChanged,
+            Some(old.description.to_string()),
+            Some(new.description.to_string()),
+        ));
+    }
+    if old.deadline != new.deadline {
+        changes.push((EventKind::DeadlineChanged, deadline(old), deadline(new)));
+    }
+    if old.priority != new.priority {
+        changes.push((
+            EventKind::PriorityChanged,
+            Some(old.priority.to_string()),
+            Some(new.priority.to_string()),
+        ));
+    }
+    if !old.same_tags(new) {
+        changes.push((
+            EventKind::TagsChanged,
+            Some(old.tags.join(" ")),
+            Some(new.tags.join(" ")),
+        ));
+    }
+    if old.recurrence != new.recurrence {
+        changes.push((
+            EventKind::RecurrenceChanged,
+            recurrence(old),
+            recurrence(new),
+        ));
+    }
+    if old.board_id != new.board_id {
+        changes.push((
+            EventKind::BoardChanged,
+            Some(old.board_id.to_string()),
+            Some(new.board_id.to_string()),
+        ));
+    }
+
+    for (kind, from, to) in changes {
+        log_event(conn, id, kind, from, to)?;
+    }
+    Ok(())
+}
+
+/// A time entry in its stored event form, an ISO 8601 `start/end` interval
+/// with an empty end while it runs.
+fn time_entry_interval(entry: &TimeEntry) -> String {
+    format!(
+        "{}/{}",
+        entry.started_at.to_rfc3339(),
+        entry.ended_at.map(|dt| dt.to_rfc3339()).unwrap_or_default()
+    )
+}
+
+fn time_entry_from_row(row: &Row) -> Result<TimeEntry, rusqlite::Error> {
+    let started_at_str: String = row.get("started_at")?;
+    let ended_at_str: Option<String> = row.get("ended_at")?;
+
+    Ok(TimeEntry {
+        id: row.get("id")?,
+        task_id: row.get("task_id")?,
+        started_at: db_timestamp_to_local_dt(&started_at_str),
+        ended_at: ended_at_str.map(|s| db_timestamp_to_local_dt(&s)),
+    })
+}
+
+fn task_from_row(row: &Row) -> Result<Task, rusqlite::Error> {
+    let status_str: String = row.get("status")?;
+    let priority_str: String = row.get("priority")?;
+
+    let created_at_str: String = row.get("created_at")?;
+
+    let created_at = db_timestamp_to_local_dt(&created_at_str);
+
+    let updated_at_str: String = row.get("updated_at")?;
+    let updated_at = db_timestamp_to_local_dt(&updated_at_str);
+
+    let maybe_deadline_str: Option<String> = row.get("deadline")?;
+    let mut deadline: Option<DateTime<Local>> = None;
+    if let Some(d_str) = maybe_deadline_str {
+        deadline = Some(db_timestamp_to_local_dt(&d_str));
+    }
+
+    let tags_str: Option<String> = row.get("tags")?;
+    let mut tags: Vec<String> = tags_str
+        .map(|s| s.split('\u{1f}').map(str::to_string).collect())
+        .unwrap_or_default();
+    tags.sort();
+
+    let recurrence_str: Option<String> = row.get("recurrence")?;
+    let recurrence = recurrence_str.and_then(|s| Recurrence::from_str(&s).ok());
+
+    let blocked_by_str: Option<String> = row.get("blocked_by")?;
+    let blocked_by: Vec<i64> = blocked_by_str
+        .map(|s| s.split(',').filter_map(|id| id.parse().ok()).collect())
+        .unwrap_or_default();
+
+    let timer_started_at_str: Option<String> = row.get("timer_started_at")?;
+    let time_spent: i64 = row.get("time_spent")?;
+    let deleted_at_str: Option<String> = row.get("deleted_at")?;
+
+    Ok(Task {
+        id: row.get("id")?,
+        board_id: row.get("board_id")?,
+        description: row.get("description")?,
+        status: Status::from_str(&status_str).map_err(|_| rusqlite::Error::UnwindingPanic)?,
+        priority: Priority::from_str(&priority_str).map_err(|_| rusqlite::Error::UnwindingPanic)?,
+        created_at,
+        updated_at,
+        deadline,
+        tags,
+        recurrence,
+        column_id: row.get("column_id")?,
+        position: row.get("position")?,
+        ordered_manually: row.get("ordered_manually")?,
+        checklist_done: row.get("checklist_done")?,
+        checklist_total: row.get("checklist_total")?,
+        blocked_by,
+        deleted_at: deleted_at_str.map(|s| db_timestamp_to_local_dt(&s)),
+        time_spent: chrono::Duration::seconds(time_spent),
+        timer_started_at: timer_started_at_str.map(|s| db_timestamp_to_local_dt(&s)),
+    })
+}
+
+/// Replaces the tags of a task and drops tags no task uses anymore. A tag
+/// that differs from an existing one only in case is that tag, under the
+/// spelling it was saved with first.
+fn save_tags(tx: &Connection, task_id: i64, tags: &[String]) -> Result<(), rusqlite::Error> {
+    tx.execute("DELETE FROM task_tags WHERE task_id = ?1", params![task_id])?;
+    let mut stmt = tx.prepare("SELECT id, name FROM tags ORDER BY id")?;
+    let mut existing = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for tag in tags {
+        let tag_id = match existing.iter().find(|(_, name)| same_tag(name, tag)) {
+            Some((id, _)) => *id,
+            None => {
+                tx.execute("INSERT INTO tags (name) VALUES (?1)", params![tag])?;
+                existing.push((tx.last_insert_rowid(), tag.to_string()));
+                tx.last_insert_rowid()
+            }
+        };
+        tx.execute(
+            "INSERT OR IGNORE INTO task_tags (task_id, tag_id) VALUES (?1, ?2)",
+            params![task_id, tag_id],
+        )?;
+    }
+    tx.execute(
+        "DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM task_tags)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// `id` if no row of `table` uses it yet; `None` lets SQLite pick a new one.
+fn free_id(tx: &Connection, table: &str, id: i64) -> Result<Option<i64>, rusqlite::Error> {
+    let taken: bool = tx.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM {table} WHERE id = ?1)"),
+        params![id],
+        |row| row.get(0),
+    )?;
+    Ok((!taken).then_some(id))
+}
+
+/// Writes the history of an imported task: the one in the file, in place of
+/// any kept for its id, or else a creation event unless the id has history
+/// already, as when a backup of this database is restored. Events of a kind
+/// this build does not know are left out.
+fn import_events(tx: &Connection, record: &TaskRecord) -> Result<(), rusqlite::Error> {
+    if record.events.is_empty() {
+        let has_history: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM task_events WHERE task_id = ?1)",
+            params![record.id],
+            |row| row.get(0),
+        )?;
+        if !has_history {
+            // The history starts when the task was created, not imported.
+            tx.execute(
+                "INSERT INTO task_events (task_id, kind, to_value, created_at)
+VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    record.id,
+                    EventKind::Created.to_string(),
+                    record.description,
+                    record.created_at.to_rfc3339()
+                ],
+            )?;
+        }
+        return Ok(());
+    }
+
+    tx.execute(
+        "DELETE FROM task_events WHERE task_id = ?1",
+        params![record.id],
+    )?;
+    for event in &record.events {
+        if EventKind::from_str(&event.kind).is_err() {
+            continue;
+        }
+        tx.execute(
+            "INSERT INTO task_events (task_id, kind, from_value, to_value, created_at)
+VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                record.id,
+                event.kind,
+                event.from,
+                event.to,
+                event.created_at.to_rfc3339()
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_column(tx: &Connection, c: &Column) -> Result<i64, rusqlite::Error> {
+    tx.execute(
+        "INSERT INTO columns (board_id, name, position, color, status, wip_limit)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6);",
+        params![
+            c.board_id,
+            c.name,
+            c.position,
+            c.color,
+            c.status.to_string(),
+            c.wip_limit
+        ],
+    )?;
+    Ok(tx.last_insert_rowid())
+}
+
+/// Column a task is stored in: its own `column_id` when that column is on the
+/// task's board, else the board's first column with the task's status, else
+/// the board's first column. Returns the column id and its status.
+fn resolve_column(tx: &Connection, t: &Task) -> Result<(i64, Status), rusqlite::Error> {
+    let (id, status_str): (i64, String) = tx.query_row(
+        "SELECT id, status FROM columns WHERE board_id = ?1
+ORDER BY id = ?2 DESC, status = ?3 DESC, position
+LIMIT 1",
+        params![t.board_id, t.column_id, t.status.to_string()],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let status = Status::from_str(&status_str).map_err(|_| rusqlite::Error::UnwindingPanic)?;
+    Ok((id, status))
+}
+
+/// Position just above the first or below the last task of a column.
+fn edge_position(
+    tx: &Connection,
+    column_id: i64,
+    insert_position: InsertPosition,
+) -> Result<i64, rusqlite::Error> {
+    let query = match insert_position {
+        InsertPosition::Top => {
+            "SELECT COALESCE(MIN(position) - 1, 0) FROM tasks WHERE column_id = ?1"
+        }
+        InsertPosition::Bottom => {
+            "SELECT COALESCE(MAX(position) + 1, 0) FROM tasks WHERE column_id = ?1"
+        }
+    };
+    tx.query_row(query, params![column_id], |row| row.get(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_differing_in_case_are_one_tag() {
+        let mut db = Database::in_memory();
+        let mut first = Task::new("first".to_string(), None, None, 1);
+        first.tags = Task::parse_tags("Work");
+        let first_id = db.insert_task(&first).unwrap();
+        let mut second = Task::new("second".to_string(), None, None, 1);
+        second.tags = Task::parse_tags("work home");
+        let second_id = db.insert_task(&second).unwrap();
+
+        assert_eq!(db.load_task(second_id).unwrap().tags, vec!["Work", "home"]);
+        assert_eq!(db.load_tags(1).unwrap().len(), 2);
+
+        // Saving the tags as typed in another case changes nothing.
+        let mut task = db.load_task(first_id).unwrap();
+        task.tags = Task::parse_tags("WORK");
+        db.update_task(&task).unwrap();
+        let events = db.load_events(first_id).unwrap();
+        assert!(events.iter().all(|e| e.kind != EventKind::TagsChanged));
+        assert_eq!(db.load_task(first_id).unwrap().tags, vec!["Work"]);
+    }
 }
@@ -7,4 +7,31 @@ pub enum AppError {
 
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("csv error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("task #{blocked_by_id} cannot block task #{task_id}: dependency cycle")]
+    DependencyCycle { task_id: i64, blocked_by_id: i64 },
+
+    #[error("task #{0} no longer exists")]
+    MissingTask(i64),
+
+    #[error("board \"{0}\" does not exist")]
+    MissingBoard(String),
+
+    #[error("a board named \"{0}\" already exists")]
+    BoardExists(String),
+
+    #[error("board has no {0} column")]
+    MissingColumn(String),
+
+    #[error("task #{task_id} is blocked by {blockers}; finish those first")]
+    Blocked { task_id: i64, blockers: String },
+
+    #[error("{column} is at its WIP limit ({limit})")]
+    WipLimit { column: String, limit: u32 },
+
+    #[error("invalid import file: {0}")]
+    InvalidImport(String),
 }
@@ -0,0 +1,365 @@
+use std::{collections::HashMap, io::Write};
+
+use chrono::{DateTime, Local};
+use strum::EnumMessage;
+use strum_macros::{Display, EnumString};
+
+use crate::{
+    board::Board,
+    db::Database,
+    error::AppError,
+    formats::{ImportReport, Origin, existing_task, run_import, save_task},
+    task::{Priority, Status, Task},
+    utils::str_to_local_dt,
+};
+
+/// Source of the ids of rows from other spreadsheets, see `Database::save_external_id`.
+const SOURCE: &str = "csv";
+
+/// A column of the spreadsheet. The header is the snake_case name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum CsvField {
+    Id,
+    Board,
+    Column,
+    Description,
+    Status,
+    Priority,
+    Tags,
+    CreatedAt,
+    UpdatedAt,
+    Deadline,
+}
+
+pub const DEFAULT_FIELDS: [CsvField; 6] = [
+    CsvField::Id,
+    CsvField::Description,
+    CsvField::Status,
+    CsvField::CreatedAt,
+    CsvField::UpdatedAt,
+    CsvField::Deadline,
+];
+
+/// Writes every task outside the trash as RFC 4180 CSV with the given columns.
+/// The `id` is the task key of this database (`12@<uuid>`), or the id the row
+/// had in the spreadsheet the task was imported from.
+pub fn export(db: &Database, out: &mut impl Write, fields: &[CsvField]) -> Result<(), AppError> {
+    let origin = Origin::load(db)?;
+    let ids = db.load_external_ids(SOURCE)?;
+    let mut boards = HashMap::new();
+    let mut columns = HashMap::new();
+    for board in db.load_boards()? {
+        let id = board.id.unwrap_or_default();
+        for column in db.load_columns(id)? {
+            columns.insert(column.id, column.name);
+        }
+        boards.insert(id, board.name);
+    }
+
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(out);
+    writer.write_record(fields.iter().map(|f| f.to_string()))?;
+    for task in db.load_all_tasks()? {
+        writer.write_record(fields.iter().map(|field| match field {
+            CsvField::Id => {
+                let id = task.id.unwrap_or_default();
+                ids.get(&id).cloned().unwrap_or_else(|| origin.task_key(id))
+            }
+            CsvField::Board => boards.get(&task.board_id).cloned().unwrap_or_default(),
+            CsvField::Column => columns.get(&task.column_id).cloned().unwrap_or_default(),
+            CsvField::Description => task.description.to_string(),
+            CsvField::Status => task.status.to_string(),
+            CsvField::Priority => task.priority.to_string(),
+            CsvField::Tags => task.tags.join(" "),
+            CsvField::CreatedAt => task.created_at.to_rfc3339(),
+            CsvField::UpdatedAt => task.updated_at.to_rfc3339(),
+            CsvField::Deadline => task.deadline.map(|d| d.to_rfc3339()).unwrap_or_default(),
+        }))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads tasks from CSV with a header row. A row whose `id` is the key of a
+/// task of this database, or the id of a row imported before, updates that
+/// task; any other row creates a task, which remembers the row's id.
+pub fn import(db: &mut Database, input: &str, dry_run: bool) -> Result<ImportReport, AppError> {
+    let mut reader = csv::Reader::from_reader(input.as_bytes());
+    let headers = reader.headers()?.clone();
+    run_import(db, dry_run, |db, report| {
+        let origin = Origin::load(db)?;
+        let mut fields = Vec::new();
+        for header in headers.iter() {
+            let field = header.trim().parse::<CsvField>().ok();
+            if field.is_none() {
+                report
+                    .skipped
+                    .push(format!("column \"{header}\": not a task field"));
+            }
+            fields.push(field);
+        }
+        if !fields.contains(&Some(CsvField::Description)) && !fields.contains(&Some(CsvField::Id)) {
+            return Err(AppError::InvalidImport(
+                "the header needs a description or an id column".to_string(),
+            ));
+        }
+
+        let boards = db.load_boards()?;
+        for (i, record) in reader.records().enumerate() {
+            // Row 1 is the header.
+            let row = i + 2;
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    report.skipped.push(format!("row {row}: {e}"));
+                    continue;
+                }
+            };
+            let values: HashMap<CsvField, &str> = fields
+                .iter()
+                .zip(record.iter())
+                .filter_map(|(field, value)| Some(((*field)?, value)))
+                .collect();
+            match row_task(db, &origin, &boards, &values) {
+                Ok((existing, task)) => {
+                    let external_id = row_id(&values)
+                        .filter(|id| origin.parse_task_key(id).is_none())
+                        .map(|id| (SOURCE, id));
+                    save_task(db, report, existing.as_ref(), &task, external_id)?;
+                }
+                Err(reason) => report.skipped.push(format!("row {row}: {reason}")),
+            }
+        }
+        Ok(())
+    })
+}
+
+/// The `id` cell of a row, unless it is empty.
+fn row_id<'a>(values: &HashMap<CsvField, &'a str>) -> Option<&'a str> {
+    values
+        .get(&CsvField::Id)
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+}
+
+/// The task a row describes, after the existing one it updates: that task
+/// with the row's values applied, or a new one. An empty deadline or tags cell clears the field; other empty
+/// cells leave it as it is.
+fn row_task(
+    db: &Database,
+    origin: &Origin,
+    boards: &[Board],
+    values: &HashMap<CsvField, &str>,
+) -> Result<(Option<Task>, Task), String> {
+    let value = |field| {
+        values
+            .get(&field)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    };
+
+    let existing = match row_id(values) {
+        Some(id) => match origin.parse_task_key(id) {
+            Some(task_id) => existing_task(db, Some(task_id), None),
+            None => existing_task(db, None, Some((SOURCE, id))),
+        }
+        .map_err(|e| e.to_string())?,
+        None => None,
+    };
+    let mut task = existing.clone().unwrap_or_else(|| {
+        Task::new(
+            String::new(),
+            None,
+            None,
+            boards.first().and_then(|b| b.id).unwrap_or(1),
+        )
+    });
+    let is_new = existing.is_none();
+
+    if let Some(name) = value(CsvField::Board) {
+        task.board_id = boards
+            .iter()
+            .find(|b| b.name.eq_ignore_ascii_case(name))
+            .and_then(|b| b.id)
+            .ok_or_else(|| format!("board \"{name}\" does not exist"))?;
+    }
+    if let Some(description) = values.get(&CsvField::Description) {
+        task.description = description.trim_end().to_string();
+    }
+    if task.description.trim().is_empty() {
+        return Err("the description is empty".to_string());
+    }
+
+    // A changed column wins over a changed status, so a row exported with
+    // both can be edited in either cell.
+    let columns = db.load_columns(task.board_id).map_err(|e| e.to_string())?;
+    let column = match value(CsvField::Column) {
+        Some(name) => Some(
+            columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("column \"{name}\" does not exist"))?,
+        ),
+        None => None,
+    };
+    let status = match value(CsvField::Status) {
+        Some(s) => Some(
+            s.parse::<Status>()
+                .map_err(|_| format!("unknown status \"{s}\""))?,
+        ),
+        None => None,
+    };
+    if let Some(column) = column.filter(|c| is_new || c.id != task.column_id) {
+        task.column_id = column.id;
+        task.status = column.status;
+        task.position = None;
+    } else if let Some(status) = status.filter(|s| is_new || *s != task.status) {
+        let column = columns
+            .iter()
+            .find(|c| c.status == status)
+            .ok_or_else(|| format!("board has no {} column", status.get_message().unwrap()))?;
+        task.column_id = column.id;
+        task.status = status;
+        task.position = None;
+    }
+
+    if let Some(p) = value(CsvField::Priority) {
+        task.priority = p
+            .parse::<Priority>()
+            .map_err(|_| format!("unknown priority \"{p}\""))?;
+    }
+    if let Some(tags) = values.get(&CsvField::Tags) {
+        task.tags = Task::parse_tags(tags);
+    }
+    if let Some(deadline) = values.get(&CsvField::Deadline) {
+        task.deadline = match deadline.trim() {
+            "" => None,
+            s => Some(parse_timestamp(s).ok_or_else(|| format!("unrecognized deadline \"{s}\""))?),
+        };
+    }
+    // Timestamps of existing tasks are kept by the database.
+    if is_new {
+        if let Some(s) = value(CsvField::CreatedAt) {
+            task.created_at =
+                parse_timestamp(s).ok_or_else(|| format!("invalid created_at \"{s}\""))?;
+        }
+        if let Some(s) = value(CsvField::UpdatedAt) {
+            task.updated_at =
+                parse_timestamp(s).ok_or_else(|| format!("invalid updated_at \"{s}\""))?;
+        }
+    }
+    Ok((existing, task))
+}
+
+/// RFC 3339 as exported, or anything the deadline field accepts.
+fn parse_timestamp(s: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|d| d.with_timezone(&Local))
+        .ok()
+        .or_else(|| str_to_local_dt(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row<'a>(cells: &[(CsvField, &'a str)]) -> HashMap<CsvField, &'a str> {
+        cells.iter().copied().collect()
+    }
+
+    fn add_task(db: &mut Database, description: &str) -> i64 {
+        db.insert_task(&Task::new(description.to_string(), None, None, 1))
+            .unwrap()
+    }
+
+    #[test]
+    fn row_with_a_key_of_this_database_updates_the_task() {
+        let mut db = Database::in_memory();
+        let id = add_task(&mut db, "old");
+        let origin = Origin::load(&db).unwrap();
+        let boards = db.load_boards().unwrap();
+        let key = origin.task_key(id);
+
+        let values = row(&[
+            (CsvField::Id, &key),
+            (CsvField::Description, "new"),
+            (CsvField::Priority, "high"),
+        ]);
+        let (existing, task) = row_task(&db, &origin, &boards, &values).unwrap();
+        assert_eq!(existing.and_then(|t| t.id), Some(id));
+        assert_eq!(task.id, Some(id));
+        assert_eq!(task.description, "new");
+        assert_eq!(task.priority, Priority::High);
+    }
+
+    #[test]
+    fn row_with_another_id_creates_a_task() {
+        let mut db = Database::in_memory();
+        let id = add_task(&mut db, "local");
+        let origin = Origin::load(&db).unwrap();
+        let boards = db.load_boards().unwrap();
+
+        for other in [
+            id.to_string(),
+            format!("{id}@0a1b2c3d-9d4a-4e7b-a1c2-5e6f7a8b9c0d"),
+            String::new(),
+        ] {
+            let values = row(&[(CsvField::Id, &other), (CsvField::Description, "theirs")]);
+            let (existing, task) = row_task(&db, &origin, &boards, &values).unwrap();
+            assert!(existing.is_none(), "{other}");
+            assert_eq!(task.id, None);
+        }
+    }
+
+    #[test]
+    fn row_rejects_unknown_values() {
+        let db = Database::in_memory();
+        let origin = Origin::load(&db).unwrap();
+        let boards = db.load_boards().unwrap();
+        for cells in [
+            vec![(CsvField::Description, " ")],
+            vec![(CsvField::Description, "a"), (CsvField::Status, "later")],
+            vec![(CsvField::Description, "a"), (CsvField::Board, "nope")],
+            vec![
+                (CsvField::Description, "a"),
+                (CsvField::Deadline, "someday"),
+            ],
+        ] {
+            assert!(row_task(&db, &origin, &boards, &row(&cells)).is_err());
+        }
+    }
+
+    #[test]
+    fn export_round_trips_within_a_database() {
+        let mut db = Database::in_memory();
+        add_task(&mut db, "first");
+        add_task(&mut db, "second,\nwith \"quotes\"");
+        let mut out = Vec::new();
+        export(&db, &mut out, &DEFAULT_FIELDS).unwrap();
+
+        let report = import(&mut db, &String::from_utf8(out).unwrap(), false).unwrap();
+        assert_eq!((report.tasks, report.updated), (0, 0));
+        assert!(report.skipped.is_empty(), "{:?}", report.skipped);
+    }
+
+    #[test]
+    fn rows_from_another_database_are_matched_on_reimport() {
+        let mut theirs = Database::in_memory();
+        add_task(&mut theirs, "theirs");
+        let mut out = Vec::new();
+        export(&theirs, &mut out, &DEFAULT_FIELDS).unwrap();
+        let input = String::from_utf8(out).unwrap();
+
+        let mut db = Database::in_memory();
+        let local = add_task(&mut db, "local");
+        let report = import(&mut db, &input, false).unwrap();
+        assert_eq!((report.tasks, report.updated), (1, 0));
+        assert_eq!(db.load_task(local).unwrap().description, "local");
+
+        let edited = input.replace("theirs", "edited");
+        let report = import(&mut db, &edited, false).unwrap();
+        assert_eq!((report.tasks, report.updated), (0, 1));
+    }
+}
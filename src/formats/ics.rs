@@ -0,0 +1,427 @@
+use std::io::Write;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+use crate::{
+    db::Database,
+    error::AppError,
+    formats::{ImportReport, Origin, existing_task, run_import, save_task},
+    task::{Priority, Status, Task},
+};
+
+/// `source` of the UIDs other calendars gave to imported to-dos.
+const SOURCE: &str = "ical";
+
+/// Writes an iCalendar with a VTODO for every task outside the trash that has
+/// a deadline. The UID comes from the task and database ids, or is the one the
+/// task was imported with, so a calendar app updates the to-do on the next
+/// import.
+pub fn export(db: &Database, out: &mut impl Write) -> Result<(), AppError> {
+    let origin = Origin::load(db)?;
+    let uids = db.load_external_ids(SOURCE)?;
+    let now = Local::now();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//rstd//rstd//EN".to_string(),
+    ];
+    for task in db.load_all_tasks()? {
+        let Some(deadline) = task.deadline else {
+            continue;
+        };
+        let id = task.id.unwrap_or_default();
+        let summary = task.description.lines().next().unwrap_or_default();
+        lines.push("BEGIN:VTODO".to_string());
+        lines.push(format!(
+            "UID:{}",
+            uids.get(&id)
+                .cloned()
+                .unwrap_or_else(|| task_uid(&origin, id))
+        ));
+        lines.push(format!("DTSTAMP:{}", utc(now)));
+        lines.push(format!("CREATED:{}", utc(task.created_at)));
+        lines.push(format!("LAST-MODIFIED:{}", utc(task.updated_at)));
+        lines.push(format!("SUMMARY:{}", escape(summary)));
+        if task.description.trim_end() != summary {
+            lines.push(format!("DESCRIPTION:{}", escape(&task.description)));
+        }
+        lines.push(format!("DUE:{}", utc(deadline)));
+        lines.push(format!("STATUS:{}", ical_status(task.status)));
+        if matches!(task.status, Status::Done | Status::Archived) {
+            lines.push(format!("COMPLETED:{}", utc(task.updated_at)));
+        }
+        if let Some(priority) = ical_priority(task.priority) {
+            lines.push(format!("PRIORITY:{priority}"));
+        }
+        if !task.tags.is_empty() {
+            let tags: Vec<String> = task.tags.iter().map(|t| escape(t)).collect();
+            lines.push(format!("CATEGORIES:{}", tags.join(",")));
+        }
+        // Reminds at the deadline in apps that only alert on alarms.
+        lines.push("BEGIN:VALARM".to_string());
+        lines.push("ACTION:DISPLAY".to_string());
+        lines.push(format!("DESCRIPTION:{}", escape(summary)));
+        lines.push("TRIGGER;RELATED=END:PT0S".to_string());
+        lines.push("END:VALARM".to_string());
+        lines.push("END:VTODO".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    for line in lines {
+        out.write_all(fold(&line).as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Reads the VTODOs of an iCalendar file; events and other components are
+/// ignored. To-dos that are not rstd's go to the first board.
+pub fn import(db: &mut Database, input: &str, dry_run: bool) -> Result<ImportReport, AppError> {
+    let origin = Origin::load(db)?;
+    run_import(db, dry_run, |db, report| {
+        let board_id = db.load_boards()?.first().and_then(|b| b.id).unwrap_or(1);
+        for (i, todo) in parse_todos(input).iter().enumerate() {
+            let uid = todo.value("UID").map(unescape);
+            let name = match &uid {
+                Some(uid) => format!("to-do {uid}"),
+                None => format!("to-do {}", i + 1),
+            };
+            let task_id = uid.as_deref().and_then(|uid| parse_task_uid(&origin, uid));
+            // UIDs of other calendars are remembered, rstd's own are not.
+            let external_id = uid
+                .as_deref()
+                .filter(|_| task_id.is_none())
+                .map(|uid| (SOURCE, uid));
+            let existing = existing_task(db, task_id, external_id)?;
+            match todo_task(existing.clone(), board_id, todo) {
+                Ok(task) => {
+                    save_task(db, report, existing.as_ref(), &task, external_id)?;
+                }
+                Err(reason) => report.skipped.push(format!("{name}: {reason}")),
+            }
+        }
+        Ok(())
+    })
+}
+
+/// The task a VTODO describes: the existing task with its properties applied,
+/// or a new one. A property missing from the to-do clears the field, as the
+/// export leaves out empty ones.
+fn todo_task(existing: Option<Task>, board_id: i64, todo: &Component) -> Result<Task, String> {
+    let is_new = existing.is_none();
+    let mut task = existing.unwrap_or_else(|| Task::new(String::new(), None, None, board_id));
+
+    let summary = todo.value("SUMMARY").map(unescape).unwrap_or_default();
+    let description = todo.value("DESCRIPTION").map(unescape);
+    task.description = match description {
+        Some(d) if d.lines().next() == Some(summary.as_str()) => d,
+        Some(d) if summary.is_empty() => d,
+        Some(d) => format!("{summary}\n{d}"),
+        None => summary,
+    };
+    if task.description.trim().is_empty() {
+        return Err("no SUMMARY".to_string());
+    }
+
+    task.deadline = match todo.property("DUE") {
+        Some(due) => Some(parse_time(due).ok_or_else(|| format!("invalid DUE \"{}\"", due.value))?),
+        None => None,
+    };
+    task.priority = match todo.value("PRIORITY") {
+        Some(p) => from_ical_priority(
+            p.trim()
+                .parse()
+                .map_err(|_| format!("invalid PRIORITY \"{p}\""))?,
+        ),
+        None => Priority::None,
+    };
+    let categories: Vec<String> = todo
+        .properties
+        .iter()
+        .filter(|p| p.name == "CATEGORIES")
+        .map(|p| unescape(&p.value))
+        .collect();
+    task.tags = Task::parse_tags(&categories.join(","));
+
+    let status = match todo.value("STATUS") {
+        Some(s) => s.trim().to_ascii_uppercase(),
+        None if todo.property("COMPLETED").is_some() => "COMPLETED".to_string(),
+        None => "NEEDS-ACTION".to_string(),
+    };
+    // Backlog and Today are both NEEDS-ACTION, so an unchanged STATUS keeps
+    // the column.
+    if is_new || status != ical_status(task.status) {
+        task.status = match status.as_str() {
+            "NEEDS-ACTION" => Status::Backlog,
+            "IN-PROCESS" => Status::InProgress,
+            "COMPLETED" => Status::Done,
+            "CANCELLED" => Status::Archived,
+            s => return Err(format!("unknown STATUS \"{s}\"")),
+        };
+        task.column_id = None;
+        task.position = None;
+    }
+
+    if is_new && let Some(created) = todo.property("CREATED").and_then(parse_time) {
+        task.created_at = created;
+    }
+    Ok(task)
+}
+
+fn task_uid(origin: &Origin, id: i64) -> String {
+    format!("task-{}", origin.task_key(id))
+}
+
+fn parse_task_uid(origin: &Origin, uid: &str) -> Option<i64> {
+    origin.parse_task_key(uid.strip_prefix("task-")?)
+}
+
+fn ical_status(status: Status) -> &'static str {
+    match status {
+        Status::Backlog | Status::Today => "NEEDS-ACTION",
+        Status::InProgress => "IN-PROCESS",
+        Status::Done | Status::Archived => "COMPLETED",
+    }
+}
+
+/// iCalendar ranks 1 (highest) to 9 (lowest), 0 being undefined.
+fn ical_priority(priority: Priority) -> Option<u8> {
+    match priority {
+        Priority::None => None,
+        Priority::Low => Some(7),
+        Priority::Medium => Some(5),
+        Priority::High => Some(3),
+        Priority::Urgent => Some(1),
+    }
+}
+
+fn from_ical_priority(priority: u8) -> Priority {
+    match priority {
+        0 => Priority::None,
+        1 => Priority::Urgent,
+        2..=4 => Priority::High,
+        5 => Priority::Medium,
+        _ => Priority::Low,
+    }
+}
+
+fn utc(dt: DateTime<Local>) -> String {
+    dt.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// A UTC time, a date or a floating time. Times with a TZID are read as
+/// local time.
+fn parse_time(property: &Property) -> Option<DateTime<Local>> {
+    let value = property.value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let dt = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&dt).with_timezone(&Local));
+    }
+    let dt = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y%m%d")
+                .ok()
+                .map(|d| d.and_time(NaiveTime::MIN))
+        })?;
+    Local.from_local_datetime(&dt).earliest()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Splits a content line into CRLF-terminated lines of at most 75 bytes.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(ch);
+        width += ch.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[derive(Debug)]
+struct Property {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Default)]
+struct Component {
+    properties: Vec<Property>,
+}
+
+impl Component {
+    fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.property(name).map(|p| p.value.as_str())
+    }
+}
+
+/// The VTODOs of the file with their own properties, leaving out those of
+/// nested components such as alarms.
+fn parse_todos(input: &str) -> Vec<Component> {
+    // Unfolds lines continued with a leading space or tab.
+    let mut lines: Vec<String> = Vec::new();
+    for line in input.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut todos = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    let mut todo = Component::default();
+    for line in lines {
+        let Some(property) = parse_line(&line) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" => stack.push(property.value.trim().to_ascii_uppercase()),
+            "END" => {
+                let component = stack.pop();
+                if component.as_deref() == Some("VTODO") {
+                    todos.push(std::mem::take(&mut todo));
+                }
+            }
+            _ if stack.last().is_some_and(|c| c == "VTODO") => todo.properties.push(property),
+            _ => {}
+        }
+    }
+    todos
+}
+
+/// `NAME;PARAM=...:value`; parameters are dropped since the value alone tells
+/// dates and times apart.
+fn parse_line(line: &str) -> Option<Property> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, ch)| match ch {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let name = line[..colon].split(';').next().unwrap_or_default();
+    Some(Property {
+        name: name.trim().to_ascii_uppercase(),
+        value: line[colon + 1..].to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_round_trips() {
+        for text in ["plain", "a, b; c\\d", "two\nlines", "trailing \\", ""] {
+            assert_eq!(unescape(&escape(text)), text);
+        }
+        assert_eq!(unescape("a\\Nb\\"), "a\nb\\");
+    }
+
+    #[test]
+    fn folds_at_75_bytes_without_splitting_characters() {
+        let line = format!("SUMMARY:{}", "é".repeat(100));
+        let folded = fold(&line);
+        assert!(folded.ends_with("\r\n"));
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= 75, "{} bytes", part.len());
+        }
+        assert_eq!(fold("SUMMARY:short"), "SUMMARY:short\r\n");
+
+        let todos = parse_todos(&format!("BEGIN:VTODO\r\n{folded}END:VTODO\r\n"));
+        assert_eq!(todos[0].value("SUMMARY"), Some("é".repeat(100).as_str()));
+    }
+
+    #[test]
+    fn parses_todos_without_nested_components() {
+        let input = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\nSUMMARY:meeting\r\nEND:VEVENT\r\n\
+            BEGIN:VTODO\r\n\
+            UID:1@example.com\r\n\
+            summary;LANGUAGE=en:pay\r\n  rent\r\n\
+            DUE;TZID=\"Europe:Kyiv\":20261024T170000\r\n\
+            BEGIN:VALARM\r\nDESCRIPTION:reminder\r\nEND:VALARM\r\n\
+            CATEGORIES:home,bills\r\n\
+            END:VTODO\r\n\
+            BEGIN:VTODO\r\nSUMMARY:second\r\nEND:VTODO\r\n\
+            END:VCALENDAR\r\n";
+        let todos = parse_todos(input);
+        assert_eq!(todos.len(), 2);
+        let todo = &todos[0];
+        assert_eq!(todo.value("SUMMARY"), Some("pay rent"));
+        assert_eq!(todo.value("DUE"), Some("20261024T170000"));
+        assert_eq!(todo.value("DESCRIPTION"), None);
+        assert_eq!(todo.value("CATEGORIES"), Some("home,bills"));
+        assert_eq!(todos[1].value("SUMMARY"), Some("second"));
+    }
+
+    #[test]
+    fn reads_todo_fields() {
+        let todos = parse_todos(
+            "BEGIN:VTODO\r\nSUMMARY:a\\, b\r\nDESCRIPTION:a\\, b\\nmore\r\nPRIORITY:2\r\n\
+             STATUS:in-process\r\nDUE;VALUE=DATE:20261024\r\nEND:VTODO\r\n",
+        );
+        let task = todo_task(None, 1, &todos[0]).unwrap();
+        assert_eq!(task.description, "a, b\nmore");
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.status, Status::InProgress);
+        assert_eq!(
+            task.deadline,
+            Local.with_ymd_and_hms(2026, 10, 24, 0, 0, 0).single()
+        );
+
+        let todos = parse_todos("BEGIN:VTODO\r\nSUMMARY:a\r\nSTATUS:LATER\r\nEND:VTODO\r\n");
+        assert!(todo_task(None, 1, &todos[0]).is_err());
+        let todos = parse_todos("BEGIN:VTODO\r\nDUE:20261024T170000Z\r\nEND:VTODO\r\n");
+        assert!(todo_task(None, 1, &todos[0]).is_err());
+    }
+
+    #[test]
+    fn export_round_trips() {
+        let mut db = Database::in_memory();
+        let deadline = Local.with_ymd_and_hms(2026, 10, 24, 17, 0, 0).single();
+        let mut task = Task::new("pay rent, now\nor later".to_string(), None, deadline, 1);
+        task.tags = vec!["bills".to_string(), "home".to_string()];
+        db.insert_task(&task).unwrap();
+        let mut out = Vec::new();
+        export(&db, &mut out).unwrap();
+
+        let report = import(&mut db, &String::from_utf8(out).unwrap(), false).unwrap();
+        assert_eq!((report.tasks, report.updated), (0, 0));
+        assert!(report.skipped.is_empty(), "{:?}", report.skipped);
+    }
+}
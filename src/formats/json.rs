@@ -0,0 +1,111 @@
+use std::io::{self, Write};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::Database,
+    error::AppError,
+    formats::{ImportMode, ImportReport},
+    task::{Priority, Status},
+};
+
+/// Version of the backup layout. Bumped whenever a field changes meaning, so
+/// older builds refuse files they cannot read correctly.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Everything in the database, as written by `rstd export --format json`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    pub schema_version: u32,
+    pub exported_at: DateTime<Local>,
+    pub boards: Vec<BoardRecord>,
+    pub tasks: Vec<TaskRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BoardRecord {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<Local>,
+    pub columns: Vec<ColumnRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColumnRecord {
+    pub id: i64,
+    pub name: String,
+    pub position: i64,
+    pub color: String,
+    pub status: Status,
+    pub wip_limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub id: i64,
+    pub board_id: i64,
+    pub column_id: Option<i64>,
+    pub status: Status,
+    pub position: Option<i64>,
+    /// Missing in backups made before cards could keep a manual order.
+    #[serde(default)]
+    pub ordered_manually: bool,
+    pub priority: Priority,
+    pub description: String,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+    pub deadline: Option<DateTime<Local>>,
+    /// Set for tasks in the trash.
+    pub deleted_at: Option<DateTime<Local>>,
+    pub tags: Vec<String>,
+    /// Repeat rule as typed in the task modal, e.g. `weekly mon,fri`.
+    pub recurrence: Option<String>,
+    /// Ids of every task blocking this one, finished or not.
+    pub blocked_by: Vec<i64>,
+    pub checklist: Vec<ChecklistRecord>,
+    pub time_entries: Vec<TimeEntryRecord>,
+    /// Activity log, oldest first. Missing in backups made before it was
+    /// exported, in which case an import keeps the history it finds.
+    #[serde(default)]
+    pub events: Vec<EventRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChecklistRecord {
+    pub description: String,
+    pub done: bool,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimeEntryRecord {
+    pub started_at: DateTime<Local>,
+    pub ended_at: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventRecord {
+    /// `status_changed` and the like, see `EventKind`.
+    pub kind: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub created_at: DateTime<Local>,
+}
+
+pub fn export(db: &Database, out: &mut impl Write) -> Result<(), AppError> {
+    serde_json::to_writer_pretty(&mut *out, &db.export_backup()?).map_err(io::Error::from)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+pub fn import(
+    db: &mut Database,
+    input: &str,
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<ImportReport, AppError> {
+    let backup: Backup =
+        serde_json::from_str(input).map_err(|e| AppError::InvalidImport(e.to_string()))?;
+    db.import_backup(&backup, mode, dry_run)
+}
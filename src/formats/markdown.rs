@@ -0,0 +1,85 @@
+use std::io::Write;
+
+use chrono::{DateTime, Local};
+
+use crate::{
+    app::card_order,
+    board::Board,
+    column::Column,
+    db::Database,
+    error::AppError,
+    task::{Status, Task},
+};
+
+/// Writes the board as Markdown: a heading per column, in board order, with
+/// a checklist item per task, Done and Archived ones checked. `columns`
+/// limits the headings to the columns with one of those names or statuses,
+/// all of them when empty, and `since` keeps only tasks changed after it.
+pub fn export(
+    db: &Database,
+    out: &mut impl Write,
+    board: &Board,
+    columns: &[String],
+    since: Option<DateTime<Local>>,
+) -> Result<(), AppError> {
+    let board_id = board.id.unwrap_or_default();
+    let board_columns = db.load_columns(board_id)?;
+    let selects = |column: &Column, name: &str| {
+        column.name.eq_ignore_ascii_case(name)
+            || name.parse::<Status>().is_ok_and(|s| s == column.status)
+    };
+    if let Some(name) = columns
+        .iter()
+        .find(|name| !board_columns.iter().any(|c| selects(c, name)))
+    {
+        return Err(AppError::MissingColumn(name.to_string()));
+    }
+    let columns: Vec<&Column> = board_columns
+        .iter()
+        .filter(|c| columns.is_empty() || columns.iter().any(|name| selects(c, name)))
+        .collect();
+    let mut tasks: Vec<Task> = db
+        .load_tasks(board_id)?
+        .into_iter()
+        .filter(|t| since.is_none_or(|since| t.updated_at >= since))
+        .collect();
+    tasks.sort_by(card_order);
+
+    writeln!(out, "# {}", board.name)?;
+    for column in columns {
+        writeln!(out)?;
+        writeln!(out, "## {}", column.name)?;
+        writeln!(out)?;
+        let mut empty = true;
+        for task in tasks.iter().filter(|t| t.column_id == column.id) {
+            writeln!(out, "{}", task_item(task))?;
+            empty = false;
+        }
+        if empty {
+            writeln!(out, "_No tasks_")?;
+        }
+    }
+    Ok(())
+}
+
+/// `- [ ] First line (due Fri 24/10/2026 17:00) #tag`, with further lines of
+/// the description indented under the item.
+fn task_item(task: &Task) -> String {
+    let done = matches!(task.status, Status::Done | Status::Archived);
+    let mut lines = task.description.lines();
+    let mut item = format!(
+        "- [{}] {}",
+        if done { "x" } else { " " },
+        lines.next().unwrap_or_default()
+    );
+    if let Some(deadline) = task.deadline {
+        item.push_str(&format!(" (due {})", deadline.format("%a %d/%m/%Y %H:%M")));
+    }
+    for tag in &task.tags {
+        item.push_str(&format!(" #{tag}"));
+    }
+    for line in lines {
+        item.push_str(&format!("\n  {line}"));
+    }
+    item
+}
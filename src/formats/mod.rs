@@ -0,0 +1,241 @@
+//! Files rstd can export its tasks to and import them from.
+
+pub mod csv;
+pub mod ics;
+pub mod json;
+pub mod markdown;
+pub mod taskwarrior;
+pub mod todotxt;
+
+use clap::ValueEnum;
+
+use crate::{db::Database, error::AppError, task::Task};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    /// Full backup of every board, column and task
+    Json,
+    /// Spreadsheet of the tasks, one row each
+    Csv,
+    /// Checklist of one board per column, export only
+    Markdown,
+    /// One line per task in the todo.txt format
+    Todotxt,
+    /// iCalendar to-dos for the tasks with a deadline
+    Ics,
+    /// JSON as read and written by `task import` and `task export`
+    Taskwarrior,
+}
+
+/// What importing does with the tasks already in the database.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ImportMode {
+    /// Keep existing tasks and add the imported ones next to them
+    Merge,
+    /// Delete everything first, so the database matches the file
+    Replace,
+}
+
+/// Outcome of an import, printed for the user.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub boards: usize,
+    /// Tasks created by the import.
+    pub tasks: usize,
+    /// Existing tasks the import changed.
+    pub updated: usize,
+    /// Tasks or rows left out, with the reason.
+    pub skipped: Vec<String>,
+}
+
+/// Runs the import of a file as one transaction, so a file that fails half
+/// way changes nothing. A dry run does the same work and then rolls it back,
+/// leaving only the report.
+///
+/// The formats share how a task from a file is matched: by a task id of this
+/// database (see `Origin`), or by an id another app gave it on an earlier
+/// import (see `existing_task`). A matched task is updated, any other
+/// created, and lines or rows that cannot be read are skipped with a reason.
+pub fn run_import(
+    db: &mut Database,
+    dry_run: bool,
+    import: impl FnOnce(&mut Database, &mut ImportReport) -> Result<(), AppError>,
+) -> Result<ImportReport, AppError> {
+    let run = |db: &mut Database| {
+        let mut report = ImportReport::default();
+        import(db, &mut report)?;
+        Ok(report)
+    };
+    if dry_run {
+        db.dry_run(run)
+    } else {
+        db.transaction(run)
+    }
+}
+
+/// The task a file names: the one with `task_id` if that came from a key of
+/// this database, or else the one imported before under the `(source, id)`
+/// of another app. Tasks in the trash count as gone.
+pub fn existing_task(
+    db: &Database,
+    task_id: Option<i64>,
+    external_id: Option<(&str, &str)>,
+) -> Result<Option<Task>, AppError> {
+    let id = match (task_id, external_id) {
+        (Some(id), _) => Some(id),
+        (None, Some((source, external_id))) => db.external_task_id(source, external_id)?,
+        (None, None) => None,
+    };
+    Ok(id.and_then(|id| db.load_task(id).ok()))
+}
+
+/// Saves a task read from a file and counts it in the report: an update of
+/// `existing` unless nothing changed, or a new task, remembered under the
+/// `(source, id)` another app gave it. Returns the id of the task when it was
+/// written.
+pub fn save_task(
+    db: &mut Database,
+    report: &mut ImportReport,
+    existing: Option<&Task>,
+    task: &Task,
+    external_id: Option<(&str, &str)>,
+) -> Result<Option<i64>, AppError> {
+    match existing {
+        Some(old) if is_unchanged(old, task) => Ok(None),
+        Some(_) => {
+            db.update_task(task)?;
+            report.updated += 1;
+            Ok(task.id)
+        }
+        None => {
+            let id = db.insert_task(task)?;
+            if let Some((source, external_id)) = external_id {
+                db.save_external_id(source, external_id, id)?;
+            }
+            report.tasks += 1;
+            Ok(Some(id))
+        }
+    }
+}
+
+/// Whether a file left a task as it was. A column of `None` lets the
+/// database pick one for the status, so it only differs when it is set.
+fn is_unchanged(old: &Task, new: &Task) -> bool {
+    old.board_id == new.board_id
+        && old.status == new.status
+        && (new.column_id.is_none() || old.column_id == new.column_id)
+        && old.description == new.description
+        && old.priority == new.priority
+        && old.same_tags(new)
+        && old.deadline == new.deadline
+}
+
+/// The database a file was exported from, part of the task ids rstd writes
+/// into it. An import only takes a file's task for one of ours when the id
+/// matches exactly, so a file from another database cannot overwrite
+/// unrelated tasks that happen to share a row id.
+pub struct Origin {
+    database_id: String,
+}
+
+impl Origin {
+    pub fn load(db: &Database) -> Result<Origin, AppError> {
+        Ok(Origin {
+            database_id: db.database_id()?,
+        })
+    }
+
+    /// `12@<database uuid>`, as todo.txt `id:` values and iCalendar UIDs.
+    pub fn task_key(&self, task_id: i64) -> String {
+        format!("{task_id}@{}", self.database_id)
+    }
+
+    pub fn parse_task_key(&self, key: &str) -> Option<i64> {
+        let (id, database_id) = key.split_once('@')?;
+        if database_id != self.database_id {
+            return None;
+        }
+        id.parse().ok()
+    }
+
+    /// A UUID for the task: the database's with the task id in its last
+    /// group.
+    pub fn task_uuid(&self, task_id: i64) -> String {
+        format!("{}-{task_id:012x}", self.uuid_prefix())
+    }
+
+    pub fn parse_task_uuid(&self, uuid: &str) -> Option<i64> {
+        let uuid = uuid.to_ascii_lowercase();
+        let hex = uuid.strip_prefix(self.uuid_prefix())?.strip_prefix('-')?;
+        if hex.len() != 12 {
+            return None;
+        }
+        i64::from_str_radix(hex, 16).ok()
+    }
+
+    fn uuid_prefix(&self) -> &str {
+        self.database_id
+            .rsplit_once('-')
+            .map_or(&self.database_id, |(prefix, _)| prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origin() -> Origin {
+        Origin {
+            database_id: "3f2b8c1e-9d4a-4e7b-a1c2-5e6f7a8b9c0d".to_string(),
+        }
+    }
+
+    #[test]
+    fn task_key_round_trips() {
+        let origin = origin();
+        for id in [1, 42, i64::MAX] {
+            assert_eq!(origin.parse_task_key(&origin.task_key(id)), Some(id));
+        }
+    }
+
+    #[test]
+    fn task_key_of_another_database_is_none() {
+        let origin = origin();
+        for key in [
+            "12@0a1b2c3d-9d4a-4e7b-a1c2-5e6f7a8b9c0d",
+            "12",
+            "@3f2b8c1e-9d4a-4e7b-a1c2-5e6f7a8b9c0d",
+            "x@3f2b8c1e-9d4a-4e7b-a1c2-5e6f7a8b9c0d",
+            "12@3f2b8c1e-9d4a-4e7b-a1c2-5e6f7a8b9c0d@x",
+            "99999999999999999999@3f2b8c1e-9d4a-4e7b-a1c2-5e6f7a8b9c0d",
+        ] {
+            assert_eq!(origin.parse_task_key(key), None, "{key}");
+        }
+    }
+
+    #[test]
+    fn task_uuid_round_trips() {
+        let origin = origin();
+        assert_eq!(origin.task_uuid(42), "3f2b8c1e-9d4a-4e7b-a1c2-00000000002a");
+        for id in [1, 42, 0xffff_ffff_ffff] {
+            assert_eq!(origin.parse_task_uuid(&origin.task_uuid(id)), Some(id));
+        }
+        assert_eq!(
+            origin.parse_task_uuid("3F2B8C1E-9D4A-4E7B-A1C2-00000000002A"),
+            Some(42)
+        );
+    }
+
+    #[test]
+    fn task_uuid_of_another_database_is_none() {
+        let origin = origin();
+        for uuid in [
+            "0a1b2c3d-9d4a-4e7b-a1c2-00000000002a",
+            "3f2b8c1e-9d4a-4e7b-a1c2-2a",
+            "3f2b8c1e-9d4a-4e7b-a1c2-00000000002a0",
+            "3f2b8c1e-9d4a-4e7b-a1c2-00000000zz2a",
+        ] {
+            assert_eq!(origin.parse_task_uuid(uuid), None, "{uuid}");
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::io::{self, Write};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    board::Board,
+    db::Database,
+    error::AppError,
+    formats::{ImportReport, Origin, existing_task, run_import, save_task},
+    task::{Priority, Status, Task},
+};
+
+/// `source` of the UUIDs of tasks imported from Taskwarrior.
+const SOURCE: &str = "taskwarrior";
+
+/// A task as `task export` writes and `task import` reads it. Attributes rstd
+/// has no use for, such as `urgency` or `recur`, are ignored.
+#[derive(Debug, Serialize, Deserialize)]
+struct TwTask {
+    uuid: String,
+    description: String,
+    status: String,
+    #[serde(default, with = "tw_time", skip_serializing_if = "Option::is_none")]
+    entry: Option<DateTime<Local>>,
+    #[serde(default, with = "tw_time", skip_serializing_if = "Option::is_none")]
+    modified: Option<DateTime<Local>>,
+    #[serde(default, with = "tw_time", skip_serializing_if = "Option::is_none")]
+    start: Option<DateTime<Local>>,
+    #[serde(default, with = "tw_time", skip_serializing_if = "Option::is_none")]
+    end: Option<DateTime<Local>>,
+    #[serde(default, with = "tw_time", skip_serializing_if = "Option::is_none")]
+    due: Option<DateTime<Local>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Annotation {
+    #[serde(default, with = "tw_time", skip_serializing_if = "Option::is_none")]
+    entry: Option<DateTime<Local>>,
+    description: String,
+}
+
+/// Taskwarrior's `20261024T170000Z` timestamps.
+mod tw_time {
+    use super::*;
+
+    const FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+    pub fn serialize<S: Serializer>(
+        dt: &Option<DateTime<Local>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match dt {
+            Some(dt) => {
+                serializer.serialize_str(&dt.with_timezone(&Utc).format(FORMAT).to_string())
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Also takes RFC 3339, which some Taskwarrior tools write.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Local>>, D::Error> {
+        let Some(s) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        NaiveDateTime::parse_from_str(&s, FORMAT)
+            .map(|dt| Utc.from_utc_datetime(&dt).with_timezone(&Local))
+            .or_else(|_| DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Local)))
+            .map(Some)
+            .map_err(|_| serde::de::Error::custom(format!("invalid timestamp \"{s}\"")))
+    }
+}
+
+/// Writes every task outside the trash as a `task import` JSON array. The
+/// board is the project, the first line of the description the description
+/// and the other lines annotations.
+pub fn export(db: &Database, out: &mut impl Write) -> Result<(), AppError> {
+    let origin = Origin::load(db)?;
+    let uuids = db.load_external_ids(SOURCE)?;
+    let boards = db.load_boards()?;
+    let mut tasks = Vec::new();
+    for task in db.load_all_tasks()? {
+        let id = task.id.unwrap_or_default();
+        let mut lines = task.description.lines().filter(|l| !l.trim().is_empty());
+        let done = matches!(task.status, Status::Done | Status::Archived);
+        tasks.push(TwTask {
+            uuid: uuids
+                .get(&id)
+                .cloned()
+                .unwrap_or_else(|| origin.task_uuid(id)),
+            description: lines.next().unwrap_or_default().to_string(),
+            status: if done { "completed" } else { "pending" }.to_string(),
+            entry: Some(task.created_at),
+            modified: Some(task.updated_at),
+            start: (task.status == Status::InProgress).then_some(task.updated_at),
+            end: done.then_some(task.updated_at),
+            due: task.deadline,
+            project: boards
+                .iter()
+                .find(|b| b.id == Some(task.board_id))
+                .map(|b| b.name.to_string()),
+            priority: tw_priority(task.priority).map(str::to_string),
+            tags: task.tags.clone(),
+            // rstd keeps no time for the extra lines, so they share the task's.
+            annotations: lines
+                .map(|line| Annotation {
+                    entry: Some(task.created_at),
+                    description: line.to_string(),
+                })
+                .collect(),
+        });
+    }
+    serde_json::to_writer_pretty(&mut *out, &tasks).map_err(io::Error::from)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Reads `task export` JSON. Projects without a board get one, and the
+/// `entry` and `modified` times are kept. Deleted Taskwarrior tasks go to the
+/// trash, and recurring templates are skipped since their instances are
+/// exported too.
+pub fn import(db: &mut Database, input: &str, dry_run: bool) -> Result<ImportReport, AppError> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(input)
+        .or_else(|_| {
+            // Older versions write one task per line instead of an array.
+            input
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect()
+        })
+        .map_err(|e| AppError::InvalidImport(e.to_string()))?;
+
+    let origin = Origin::load(db)?;
+    run_import(db, dry_run, |db, report| {
+        let mut boards = db.load_boards()?;
+        for (i, value) in values.into_iter().enumerate() {
+            let tw: TwTask = match serde_json::from_value(value) {
+                Ok(tw) => tw,
+                Err(e) => {
+                    report.skipped.push(format!("task {}: {e}", i + 1));
+                    continue;
+                }
+            };
+            let name = format!("task {}", tw.uuid);
+            let task_id = origin.parse_task_uuid(&tw.uuid);
+            // UUIDs from Taskwarrior are remembered, rstd's own are not.
+            let external_id = Some((SOURCE, tw.uuid.as_str())).filter(|_| task_id.is_none());
+            let existing = existing_task(db, task_id, external_id)?;
+
+            match tw.status.as_str() {
+                "pending" | "waiting" | "completed" => {}
+                "deleted" => {
+                    match existing {
+                        Some(task) => {
+                            db.delete_task(&task)?;
+                            report.updated += 1;
+                        }
+                        None => report
+                            .skipped
+                            .push(format!("{name}: deleted in Taskwarrior")),
+                    }
+                    continue;
+                }
+                "recurring" => {
+                    report.skipped.push(format!(
+                        "{name}: recurring template, its instances are imported"
+                    ));
+                    continue;
+                }
+                s => {
+                    report
+                        .skipped
+                        .push(format!("{name}: unknown status \"{s}\""));
+                    continue;
+                }
+            }
+
+            let board_id = match &tw.project {
+                Some(project) => {
+                    match boards.iter().find(|b| b.name.eq_ignore_ascii_case(project)) {
+                        Some(board) => board.id.unwrap_or_default(),
+                        None => {
+                            let mut board = Board::new(project.to_string());
+                            let id = db.insert_board(&board)?;
+                            board.id = Some(id);
+                            boards.push(board);
+                            report.boards += 1;
+                            id
+                        }
+                    }
+                }
+                None => match &existing {
+                    Some(task) => task.board_id,
+                    None => boards.first().and_then(|b| b.id).unwrap_or(1),
+                },
+            };
+            let task = tw_task(existing.clone(), board_id, &tw);
+            if task.description.trim().is_empty() {
+                report
+                    .skipped
+                    .push(format!("{name}: the description is empty"));
+                continue;
+            }
+            let saved = save_task(db, report, existing.as_ref(), &task, external_id)?;
+            if let (Some(id), Some(_), Some(modified)) = (saved, &existing, tw.modified) {
+                db.set_task_updated_at(id, modified)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// The task a Taskwarrior task describes: the existing task with its
+/// attributes applied, or a new one.
+fn tw_task(existing: Option<Task>, board_id: i64, tw: &TwTask) -> Task {
+    let is_new = existing.is_none();
+    let mut task = existing.unwrap_or_else(|| Task::new(String::new(), None, None, board_id));
+
+    let mut lines = vec![tw.description.trim()];
+    lines.extend(tw.annotations.iter().map(|a| a.description.trim()));
+    task.description = lines.join("\n");
+    task.deadline = tw.due;
+    task.tags = Task::parse_tags(&tw.tags.join(" "));
+    // Taskwarrior has no urgent priority and no Today or Archived status, so
+    // values that map back to what the task has keep it.
+    task.priority = match tw.priority.as_deref() {
+        Some("H") if task.priority == Priority::Urgent => Priority::Urgent,
+        Some("H") => Priority::High,
+        Some("M") => Priority::Medium,
+        Some("L") => Priority::Low,
+        _ => Priority::None,
+    };
+    let status = match tw.status.as_str() {
+        "completed" if matches!(task.status, Status::Done | Status::Archived) => task.status,
+        "completed" => Status::Done,
+        _ if tw.start.is_some() => Status::InProgress,
+        _ if matches!(task.status, Status::Backlog | Status::Today) && !is_new => task.status,
+        _ => Status::Backlog,
+    };
+    if status != task.status || board_id != task.board_id {
+        task.status = status;
+        task.board_id = board_id;
+        task.column_id = None;
+        task.position = None;
+    }
+
+    if is_new {
+        if let Some(entry) = tw.entry {
+            task.created_at = entry;
+        }
+        task.updated_at = tw.modified.or(tw.end).unwrap_or(task.created_at);
+    }
+    task
+}
+
+fn tw_priority(priority: Priority) -> Option<&'static str> {
+    match priority {
+        Priority::None => None,
+        Priority::Low => Some("L"),
+        Priority::Medium => Some("M"),
+        Priority::High | Priority::Urgent => Some("H"),
+    }
+}
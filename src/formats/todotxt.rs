@@ -0,0 +1,648 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+use crate::{
+    board::Board,
+    db::Database,
+    error::AppError,
+    formats::{ImportReport, Origin, existing_task, run_import, save_task},
+    task::{Priority, Status, Task},
+};
+
+/// `source` of the `id:` keys of lines from other databases.
+const SOURCE: &str = "todotxt";
+
+/// One line of a todo.txt file.
+///
+/// `+project` is the board, `@context` the tags, `(A)`..`(D)` the priority
+/// from urgent to low and `x` a done task. The `status:` and `id:` keys carry
+/// what todo.txt has no syntax for, so a file written by rstd reads back
+/// without losing anything but line breaks in descriptions. The `id:` is the
+/// task's key, see `Keys`.
+#[derive(Debug, Clone)]
+struct TodoItem {
+    done: bool,
+    completed: Option<NaiveDate>,
+    created: Option<NaiveDate>,
+    priority: Priority,
+    description: String,
+    project: Option<String>,
+    contexts: Vec<String>,
+    due: Option<DateTime<Local>>,
+    status: Status,
+    id: Option<String>,
+}
+
+impl TodoItem {
+    /// Parses a line; blank lines yield `None`.
+    fn parse(line: &str) -> Option<TodoItem> {
+        let mut words = line.split_whitespace().peekable();
+        words.peek()?;
+        let mut item = TodoItem {
+            done: false,
+            completed: None,
+            created: None,
+            priority: Priority::None,
+            description: String::new(),
+            project: None,
+            contexts: Vec::new(),
+            due: None,
+            status: Status::Backlog,
+            id: None,
+        };
+        let mut status = None;
+
+        if words.next_if_eq(&"x").is_some() {
+            item.done = true;
+            item.completed = words
+                .next_if(|w| parse_date(w).is_some())
+                .and_then(parse_date);
+        }
+        if let Some(priority) = words.peek().and_then(|w| parse_priority(w)) {
+            item.priority = priority;
+            words.next();
+        }
+        item.created = words
+            .next_if(|w| parse_date(w).is_some())
+            .and_then(parse_date);
+
+        let mut description = Vec::new();
+        for word in words {
+            if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+                match item.project {
+                    None => item.project = Some(project.to_string()),
+                    Some(_) => item.contexts.push(project.to_string()),
+                }
+                continue;
+            }
+            if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+                item.contexts.push(context.to_string());
+                continue;
+            }
+            let known = match word.split_once(':') {
+                Some(("due", value)) => parse_due(value).map(|due| item.due = Some(due)),
+                Some(("status", value)) => value.parse().ok().map(|s| status = Some(s)),
+                Some(("id", value)) => Some(value)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| item.id = Some(v.to_string())),
+                Some(("pri", value)) => {
+                    parse_priority(&format!("({value})")).map(|priority| item.priority = priority)
+                }
+                _ => None,
+            };
+            if known.is_none() {
+                description.push(word);
+            }
+        }
+        item.description = description.join(" ");
+        item.contexts = Task::parse_tags(&item.contexts.join(" "));
+        item.status = match (item.done, status) {
+            (true, Some(Status::Archived)) => Status::Archived,
+            (true, _) => Status::Done,
+            (false, Some(s)) if !matches!(s, Status::Done | Status::Archived) => s,
+            (false, _) => Status::Backlog,
+        };
+        Some(item)
+    }
+
+    fn from_task(task: &Task, board: &str, keys: &Keys) -> TodoItem {
+        let done = matches!(task.status, Status::Done | Status::Archived);
+        TodoItem {
+            done,
+            completed: done.then(|| task.updated_at.date_naive()),
+            created: Some(task.created_at.date_naive()),
+            priority: task.priority,
+            description: flatten(&task.description),
+            project: Some(project_name(board)),
+            contexts: Task::parse_tags(&task.tags.join(" ")),
+            due: task.deadline,
+            status: task.status,
+            id: task.id.map(|id| keys.key(id)),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        let mut words = Vec::new();
+        let letter = priority_letter(self.priority);
+        if self.done {
+            words.push("x".to_string());
+            words.extend(self.completed.map(|d| d.format("%Y-%m-%d").to_string()));
+        } else if let Some(letter) = letter {
+            words.push(format!("({letter})"));
+        }
+        words.extend(self.created.map(|d| d.format("%Y-%m-%d").to_string()));
+        if !self.description.is_empty() {
+            words.push(self.description.to_string());
+        }
+        words.extend(self.project.iter().map(|p| format!("+{p}")));
+        words.extend(self.contexts.iter().map(|c| format!("@{c}")));
+        if self.done
+            && let Some(letter) = letter
+        {
+            words.push(format!("pri:{letter}"));
+        }
+        if let Some(due) = self.due {
+            words.push(match due.time() {
+                NaiveTime::MIN => format!("due:{}", due.format("%Y-%m-%d")),
+                _ => format!("due:{}", due.format("%Y-%m-%dT%H:%M")),
+            });
+        }
+        if !matches!(self.status, Status::Backlog | Status::Done) {
+            words.push(format!("status:{}", self.status));
+        }
+        words.extend(self.id.iter().map(|id| format!("id:{id}")));
+        words.join(" ")
+    }
+
+    /// Whether both describe the same task state; dates of creation and
+    /// completion are informational and not compared.
+    fn same(&self, other: &TodoItem) -> bool {
+        self.done == other.done
+            && self.priority == other.priority
+            && self.description == other.description
+            && self
+                .project
+                .as_deref()
+                .map(str::to_lowercase)
+                .eq(&other.project.as_deref().map(str::to_lowercase))
+            && self.contexts == other.contexts
+            && self.due == other.due
+            && self.status == other.status
+            && self.id == other.id
+    }
+
+    /// The task of this database the line is about, if its `id:` is ours.
+    fn task_id(&self, origin: &Origin) -> Option<i64> {
+        origin.parse_task_key(self.id.as_deref()?)
+    }
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
+
+/// `2026-10-24` or, with a time of day, `2026-10-24T17:00`.
+fn parse_due(s: &str) -> Option<DateTime<Local>> {
+    let dt = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M")
+        .ok()
+        .or_else(|| parse_date(s).map(|d| d.and_time(NaiveTime::MIN)))?;
+    Local.from_local_datetime(&dt).earliest()
+}
+
+fn parse_priority(s: &str) -> Option<Priority> {
+    match s {
+        "(A)" => Some(Priority::Urgent),
+        "(B)" => Some(Priority::High),
+        "(C)" => Some(Priority::Medium),
+        "(D)" => Some(Priority::Low),
+        // Lower priorities than rstd has still mark the task as prioritized.
+        s if s.len() == 3
+            && s.starts_with('(')
+            && s.ends_with(')')
+            && s.as_bytes()[1].is_ascii_uppercase() =>
+        {
+            Some(Priority::Low)
+        }
+        _ => None,
+    }
+}
+
+fn priority_letter(priority: Priority) -> Option<char> {
+    match priority {
+        Priority::None => None,
+        Priority::Low => Some('D'),
+        Priority::Medium => Some('C'),
+        Priority::High => Some('B'),
+        Priority::Urgent => Some('A'),
+    }
+}
+
+/// todo.txt is one task per line, so line breaks become spaces.
+fn flatten(description: &str) -> String {
+    description.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Board name as a `+project`, which cannot contain spaces.
+fn project_name(board: &str) -> String {
+    board.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+/// The `id:` keys of the tasks: the key of this database, or the one the line
+/// had in a file of another database when the task came from there.
+struct Keys {
+    origin: Origin,
+    external: HashMap<i64, String>,
+}
+
+impl Keys {
+    fn load(db: &Database) -> Result<Keys, AppError> {
+        Ok(Keys {
+            origin: Origin::load(db)?,
+            external: db.load_external_ids(SOURCE)?,
+        })
+    }
+
+    fn key(&self, task_id: i64) -> String {
+        self.external
+            .get(&task_id)
+            .cloned()
+            .unwrap_or_else(|| self.origin.task_key(task_id))
+    }
+
+    /// The task a line is about, whether its key is ours or another
+    /// database's seen before.
+    fn task_id(&self, item: &TodoItem) -> Option<i64> {
+        let key = item.id.as_deref()?;
+        self.origin.parse_task_key(key).or_else(|| {
+            self.external
+                .iter()
+                .find(|(_, external)| *external == key)
+                .map(|(id, _)| *id)
+        })
+    }
+
+    /// Keeps the key of a line from another database for the task created
+    /// from it. Keys of this database are not kept: the new task has its own.
+    fn remember(
+        &mut self,
+        db: &mut Database,
+        item: &TodoItem,
+        task_id: i64,
+    ) -> Result<(), AppError> {
+        let Some(key) = item
+            .id
+            .as_deref()
+            .filter(|key| self.origin.parse_task_key(key).is_none())
+        else {
+            return Ok(());
+        };
+        db.save_external_id(SOURCE, key, task_id)?;
+        self.external.insert(task_id, key.to_string());
+        Ok(())
+    }
+}
+
+/// Boards of the database, created on demand for unknown projects.
+struct Boards {
+    boards: Vec<Board>,
+    created: usize,
+}
+
+impl Boards {
+    fn load(db: &Database) -> Result<Boards, AppError> {
+        Ok(Boards {
+            boards: db.load_boards()?,
+            created: 0,
+        })
+    }
+
+    fn name(&self, board_id: i64) -> &str {
+        self.boards
+            .iter()
+            .find(|b| b.id == Some(board_id))
+            .map_or("", |b| b.name.as_str())
+    }
+
+    /// Board of a `+project`, the first board without one.
+    fn id(&mut self, db: &mut Database, project: Option<&str>) -> Result<i64, AppError> {
+        let Some(project) = project else {
+            return Ok(self.boards.first().and_then(|b| b.id).unwrap_or(1));
+        };
+        if let Some(board) = self
+            .boards
+            .iter()
+            .find(|b| project_name(&b.name).eq_ignore_ascii_case(project))
+        {
+            return Ok(board.id.unwrap_or_default());
+        }
+        let mut board = Board::new(project.replace('_', " "));
+        let id = db.insert_board(&board)?;
+        board.id = Some(id);
+        self.boards.push(board);
+        self.created += 1;
+        Ok(id)
+    }
+}
+
+/// Writes every task outside the trash as one todo.txt line.
+pub fn export(db: &Database, out: &mut impl Write) -> Result<(), AppError> {
+    let keys = Keys::load(db)?;
+    let boards = Boards::load(db)?;
+    for task in db.load_all_tasks()? {
+        writeln!(
+            out,
+            "{}",
+            TodoItem::from_task(&task, boards.name(task.board_id), &keys).to_line()
+        )?;
+    }
+    Ok(())
+}
+
+/// Reads todo.txt lines, creating boards for unknown projects.
+pub fn import(db: &mut Database, input: &str, dry_run: bool) -> Result<ImportReport, AppError> {
+    let origin = Origin::load(db)?;
+    run_import(db, dry_run, |db, report| {
+        let mut boards = Boards::load(db)?;
+        for item in input.lines().filter_map(TodoItem::parse) {
+            let task_id = item.task_id(&origin);
+            // Keys of other databases are remembered, ours are not.
+            let external_id = item
+                .id
+                .as_deref()
+                .filter(|_| task_id.is_none())
+                .map(|key| (SOURCE, key));
+            let existing = existing_task(db, task_id, external_id)?;
+            let task = match existing.clone() {
+                Some(mut task) => {
+                    apply(db, &mut boards, &item, &mut task)?;
+                    task
+                }
+                None => new_task(db, &mut boards, &item)?,
+            };
+            save_task(db, report, existing.as_ref(), &task, external_id)?;
+        }
+        report.boards = boards.created;
+        Ok(())
+    })
+}
+
+/// Copies the fields of a line onto a task. A description that only differs
+/// in line breaks is kept, since todo.txt cannot hold them.
+fn apply(
+    db: &mut Database,
+    boards: &mut Boards,
+    item: &TodoItem,
+    task: &mut Task,
+) -> Result<(), AppError> {
+    if flatten(&task.description) != item.description {
+        task.description = item.description.to_string();
+    }
+    task.priority = item.priority;
+    task.tags = Task::parse_tags(&item.contexts.join(" "));
+    task.deadline = item.due;
+    let board_id = boards.id(db, item.project.as_deref())?;
+    if board_id != task.board_id || item.status != task.status {
+        task.board_id = board_id;
+        task.status = item.status;
+        task.column_id = db
+            .load_columns(board_id)?
+            .into_iter()
+            .find(|c| c.status == item.status)
+            .and_then(|c| c.id);
+        task.position = None;
+    }
+    Ok(())
+}
+
+fn new_task(db: &mut Database, boards: &mut Boards, item: &TodoItem) -> Result<Task, AppError> {
+    let board_id = boards.id(db, item.project.as_deref())?;
+    let mut task = Task::new(item.description.to_string(), None, None, board_id);
+    if let Some(created) = item.created.and_then(|d| {
+        Local
+            .from_local_datetime(&d.and_time(NaiveTime::MIN))
+            .earliest()
+    }) {
+        task.created_at = created;
+    }
+    task.status = Status::Backlog;
+    apply(db, boards, item, &mut task)?;
+    Ok(task)
+}
+
+/// Outcome of a sync, printed for the user.
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// Tasks created, updated or moved to the trash from the file.
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    /// Lines added or rewritten from rstd, or removed for deleted tasks.
+    pub written: usize,
+    pub removed: usize,
+    pub conflicts: Vec<String>,
+}
+
+/// Merges the file and the database both ways.
+///
+/// The file as rstd last wrote it is kept in the database, so a line counts
+/// as edited when it differs from that copy and a task when its `updated_at`
+/// is after the last sync. When both sides changed, the later of `updated_at`
+/// and the file's modification time wins. Tasks deleted on one side are
+/// deleted on the other, unless the other side changed them meanwhile.
+///
+/// Lines keep their `id:`, so a line from another database's file is the
+/// same task on every sync. The database changes are one transaction,
+/// committed before the file is replaced by renaming a complete copy over
+/// it, so a failed sync leaves the file as it was.
+pub fn sync(db: &mut Database, path: &Path) -> Result<SyncReport, AppError> {
+    let key = std::path::absolute(path)?.display().to_string();
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let modified: Option<DateTime<Local>> = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(DateTime::from);
+    let (synced_at, base) = match db.load_sync_state(&key)? {
+        Some((synced_at, base)) => (Some(synced_at), base),
+        None => (None, String::new()),
+    };
+    let (report, lines) = db.transaction(|db| {
+        let mut keys = Keys::load(db)?;
+        let base: HashMap<i64, TodoItem> = base
+            .lines()
+            .filter_map(TodoItem::parse)
+            .filter_map(|item| Some((keys.task_id(&item)?, item)))
+            .collect();
+        let tasks: Vec<Task> = db.load_all_tasks()?;
+        let mut boards = Boards::load(db)?;
+        let mut report = SyncReport::default();
+
+        // Ids of the tasks the file lists after the sync, in file order.
+        let mut ids = Vec::new();
+        let mut seen = HashSet::new();
+        for item in contents.lines().filter_map(TodoItem::parse) {
+            let task_id = keys.task_id(&item);
+            if task_id.is_some_and(|id| seen.contains(&id)) {
+                continue;
+            }
+            let task = task_id.and_then(|id| tasks.iter().find(|t| t.id == Some(id)));
+            let Some(task) = task else {
+                let synced = task_id.and_then(|id| Some((id, base.get(&id)?)));
+                if let Some((id, synced)) = synced {
+                    if synced.same(&item) {
+                        // Synced before and deleted in rstd since.
+                        report.removed += 1;
+                        continue;
+                    }
+                    // Changed in the file after it was deleted in rstd, so the
+                    // change brings the task back, unless it was purged.
+                    match db.restore_task(id) {
+                        Ok(()) => {
+                            let mut task = db.load_task(id)?;
+                            apply(db, &mut boards, &item, &mut task)?;
+                            db.update_task(&task)?;
+                            report.updated += 1;
+                            report.conflicts.push(format!(
+                            "task #{id} was deleted in rstd but changed in todo.txt; restored it"
+                        ));
+                            seen.insert(id);
+                            ids.push(id);
+                            continue;
+                        }
+                        Err(AppError::MissingTask(_)) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                let task = new_task(db, &mut boards, &item)?;
+                let id = db.insert_task(&task)?;
+                keys.remember(db, &item, id)?;
+                report.created += 1;
+                seen.insert(id);
+                ids.push(id);
+                continue;
+            };
+            let id = task.id.unwrap_or_default();
+            seen.insert(id);
+            ids.push(id);
+            if TodoItem::from_task(task, boards.name(task.board_id), &keys).same(&item) {
+                continue;
+            }
+            let file_changed = base.get(&id).is_none_or(|b| !b.same(&item));
+            let db_changed = synced_at.is_none_or(|t| task.updated_at > t);
+            let file_wins = match (file_changed, db_changed) {
+                (true, true) => {
+                    let file_wins = modified.is_some_and(|m| m > task.updated_at);
+                    report.conflicts.push(format!(
+                        "task #{id} changed on both sides; kept the {} version",
+                        if file_wins { "todo.txt" } else { "rstd" }
+                    ));
+                    file_wins
+                }
+                (file_changed, _) => file_changed,
+            };
+            if file_wins {
+                let mut task = task.clone();
+                apply(db, &mut boards, &item, &mut task)?;
+                db.update_task(&task)?;
+                report.updated += 1;
+            } else {
+                report.written += 1;
+            }
+        }
+
+        for task in tasks
+            .iter()
+            .filter(|t| t.id.is_some_and(|id| !seen.contains(&id)))
+        {
+            let id = task.id.unwrap_or_default();
+            let unchanged = synced_at.is_some_and(|t| task.updated_at <= t);
+            if base.contains_key(&id) && unchanged {
+                // Synced before and deleted from the file since.
+                db.delete_task(task)?;
+                report.deleted += 1;
+            } else {
+                ids.push(id);
+                report.written += 1;
+            }
+        }
+
+        let mut lines = String::new();
+        for id in ids {
+            let task = db.load_task(id)?;
+            lines
+                .push_str(&TodoItem::from_task(&task, boards.name(task.board_id), &keys).to_line());
+            lines.push('\n');
+        }
+        db.save_sync_state(&key, Local::now(), &lines)?;
+        Ok((report, lines))
+    })?;
+    write_atomically(path, &lines)?;
+    Ok(report)
+}
+
+/// Replaces `path` with `contents` through a temporary file next to it, so
+/// readers see either the old file or the new one and never a partial write.
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".rstd-tmp");
+    let tmp = path.with_file_name(name);
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_line() {
+        let item = TodoItem::parse(
+            "x 2026-10-20 2026-10-01 call Bob +Home_Office @phone +errands pri:B due:2026-10-24T17:00 status:archived id:7@abc",
+        )
+        .unwrap();
+        assert!(item.done);
+        assert_eq!(item.completed, NaiveDate::from_ymd_opt(2026, 10, 20));
+        assert_eq!(item.created, NaiveDate::from_ymd_opt(2026, 10, 1));
+        assert_eq!(item.priority, Priority::High);
+        assert_eq!(item.description, "call Bob");
+        assert_eq!(item.project.as_deref(), Some("Home_Office"));
+        assert_eq!(item.contexts.len(), 2);
+        assert_eq!(item.due, parse_due("2026-10-24T17:00"));
+        assert_eq!(item.status, Status::Archived);
+        assert_eq!(item.id.as_deref(), Some("7@abc"));
+    }
+
+    #[test]
+    fn keeps_unknown_keys_in_the_description() {
+        let item = TodoItem::parse("(Z) read https://example.com due:soon id:").unwrap();
+        assert_eq!(item.priority, Priority::Low);
+        assert_eq!(item.description, "read https://example.com due:soon id:");
+        assert_eq!(item.id, None);
+        assert!(TodoItem::parse("   ").is_none());
+    }
+
+    #[test]
+    fn line_round_trips() {
+        for line in [
+            "(A) 2026-10-01 pay rent +Default @home due:2026-10-24",
+            "x 2026-10-20 2026-10-01 ship it +Work pri:C status:archived id:3@abc",
+            "2026-10-01 draft +Work due:2026-10-24T09:30 status:in_progress",
+        ] {
+            let item = TodoItem::parse(line).unwrap();
+            assert_eq!(item.to_line(), line);
+            assert!(TodoItem::parse(&item.to_line()).unwrap().same(&item));
+        }
+    }
+
+    #[test]
+    fn sync_keeps_the_keys_of_another_database() {
+        let dir = std::env::temp_dir().join(format!("rstd-todotxt-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("todo.txt");
+        fs::write(&path, "buy milk +Default id:7@other\n").unwrap();
+
+        let mut db = Database::in_memory();
+        let report = sync(&mut db, &path).unwrap();
+        assert_eq!(report.created, 1);
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.trim_end().ends_with("id:7@other"), "{written}");
+
+        fs::write(&path, written.replace("buy milk", "buy oat milk")).unwrap();
+        let report = sync(&mut db, &path).unwrap();
+        assert_eq!((report.created, report.updated), (0, 1));
+        assert_eq!(db.load_all_tasks().unwrap().len(), 1);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,38 +1,93 @@
+mod activity;
 mod app;
+mod board;
+mod checklist;
+mod cli;
+mod column;
+mod config;
 mod db;
 mod error;
+mod formats;
 mod migrator;
+mod recurrence;
 mod task;
+mod text_input;
+mod time_entry;
 mod ui;
+mod undo;
 mod utils;
 
+use chrono::{Duration, Local};
+use clap::Parser;
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io;
 
 use crate::{
-    app::{AppMode, AppState, TaskModalState},
+    app::{
+        AppMode, AppState, BoardModalState, ChecklistModalState, ColumnModalState, ModalField,
+        TaskModalState,
+    },
+    board::Board,
+    cli::Cli,
+    config::Config,
     db::Database,
     error::AppError,
     task::Task,
-    ui::{render_board, render_task_modal},
+    ui::{
+        render_board, render_board_modal, render_boards, render_checklist, render_checklist_modal,
+        render_column_modal, render_columns, render_dependencies, render_history,
+        render_task_modal, render_time_entries, render_time_report, render_trash,
+    },
 };
 use crossterm::{
     ExecutableCommand,
     event::{self, Event, KeyCode, KeyModifiers},
-    terminal::{EnterAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 
+const TICK: std::time::Duration = std::time::Duration::from_secs(60);
+
 fn main() -> Result<(), AppError> {
-    enable_raw_mode()?;
-    io::stdout().execute(EnterAlternateScreen)?;
+    if let Some(command) = Cli::parse().command {
+        match cli::run(command) {
+            // The reader went away, e.g. `rstd list | head`.
+            Err(AppError::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => {}
+            Err(e) => {
+                eprintln!("rstd: {e}");
+                std::process::exit(1);
+            }
+            Ok(()) => {}
+        }
+        return Ok(());
+    }
+
+    let _guard = TerminalGuard::enter()?;
     let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
     let mut db = Database::new().expect("Failed to connect to DB");
     db.apply_migrations().expect("Failed to apply migrations");
+    let config = Config::load();
+    db.insert_position = config.insert_position;
+    let purged = match config.trash_retention_days {
+        0 => 0,
+        days => db
+            .purge_trash(Local::now() - Duration::days(days as i64))
+            .expect("Failed to empty the trash"),
+    };
 
     let mut app = AppState::new(&db);
+    app.config = config;
+    if purged > 0 {
+        app.status_message = Some(format!("Purged {purged} task(s) from the trash"));
+    }
+    if let Err(e) = app.run_policies(&mut db) {
+        app.status_message = Some(e.to_string());
+    }
     let mut modal_state = TaskModalState::new();
+    let mut board_modal_state = BoardModalState::new();
+    let mut checklist_modal_state = ChecklistModalState::new();
+    let mut column_modal_state = ColumnModalState::new();
 
     loop {
         match app.mode {
@@ -41,68 +96,670 @@ fn main() -> Result<(), AppError> {
                     render_board(frame, &app);
                 })?;
             }
-            AppMode::NewTask => {
+            AppMode::NewTask | AppMode::EditTask => {
                 let _ = terminal.draw(|frame| {
                     render_task_modal(frame, &modal_state);
                 })?;
             }
+            AppMode::Boards => {
+                let _ = terminal.draw(|frame| {
+                    render_boards(frame, &app);
+                })?;
+            }
+            AppMode::NewBoard | AppMode::EditBoard => {
+                let _ = terminal.draw(|frame| {
+                    render_board_modal(frame, &board_modal_state, app.status_message.as_deref());
+                })?;
+            }
+            AppMode::Checklist => {
+                let _ = terminal.draw(|frame| {
+                    render_checklist(frame, &app);
+                })?;
+            }
+            AppMode::Columns => {
+                let _ = terminal.draw(|frame| {
+                    render_columns(frame, &app);
+                })?;
+            }
+            AppMode::NewColumn | AppMode::EditColumn => {
+                let _ = terminal.draw(|frame| {
+                    render_column_modal(frame, &column_modal_state, app.status_message.as_deref());
+                })?;
+            }
+            AppMode::Dependencies => {
+                let _ = terminal.draw(|frame| {
+                    render_dependencies(frame, &app);
+                })?;
+            }
+            AppMode::History => {
+                let _ = terminal.draw(|frame| {
+                    render_history(frame, &app);
+                })?;
+            }
+            AppMode::TimeEntries => {
+                let _ = terminal.draw(|frame| {
+                    render_time_entries(frame, &app);
+                })?;
+            }
+            AppMode::TimeReport => {
+                let _ = terminal.draw(|frame| {
+                    render_time_report(frame, &app);
+                })?;
+            }
+            AppMode::Trash => {
+                let _ = terminal.draw(|frame| {
+                    render_trash(frame, &app);
+                })?;
+            }
+            AppMode::NewChecklistItem | AppMode::EditChecklistItem => {
+                let _ = terminal.draw(|frame| {
+                    render_checklist_modal(
+                        frame,
+                        &checklist_modal_state,
+                        app.status_message.as_deref(),
+                    );
+                })?;
+            }
+        }
+
+        if let Err(e) = handle_events(
+            &mut app,
+            &mut db,
+            &mut modal_state,
+            &mut board_modal_state,
+            &mut checklist_modal_state,
+            &mut column_modal_state,
+        ) {
+            // Failing to read the terminal cannot be shown on it.
+            if let AppError::Io(e) = e {
+                return Err(e.into());
+            }
+            app.status_message = Some(e.to_string());
+        }
+
+        if app.should_quit {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Raw mode and the alternate screen, undone when dropped so that the terminal
+/// is restored however the board exits, errors and panics included.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<TerminalGuard> {
+        enable_raw_mode()?;
+        let guard = TerminalGuard;
+        io::stdout().execute(EnterAlternateScreen)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = io::stdout().execute(LeaveAlternateScreen);
+    }
+}
+
+/// Waits for the next key press or tick and applies it to the app. Errors
+/// are returned to be shown in the status line instead of ending the program.
+fn handle_events(
+    app: &mut AppState,
+    db: &mut Database,
+    modal_state: &mut TaskModalState,
+    board_modal_state: &mut BoardModalState,
+    checklist_modal_state: &mut ChecklistModalState,
+    column_modal_state: &mut ColumnModalState,
+) -> Result<(), AppError> {
+    loop {
+        // Wakes up every minute to redraw running timers and to run the
+        // daily policies at midnight.
+        if !event::poll(TICK)? {
+            app.run_policies(db)?;
+            break;
         }
+        if let Event::Key(key_event) = event::read()? {
+            app.status_message = None;
+            match app.mode {
+                AppMode::Board => match key_event.code {
+                    KeyCode::Char('q') => {
+                        app.should_quit = true;
+                        break;
+                    }
+                    KeyCode::Tab => {
+                        app.select_next_column();
+                        break;
+                    }
+                    KeyCode::Left => {
+                        app.move_task_to_column(db, app.prev_column_index())?;
+                        break;
+                    }
+                    KeyCode::Right => {
+                        app.move_task_to_column(db, app.next_column_index())?;
+                        break;
+                    }
+                    KeyCode::Down if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                        app.shift_task(db, 1)?;
+                        break;
+                    }
+                    KeyCode::Up if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                        app.shift_task(db, -1)?;
+                        break;
+                    }
+                    KeyCode::Down => {
+                        app.select_next_task();
+                        break;
+                    }
+                    KeyCode::Up => {
+                        app.select_prev_task();
+                        break;
+                    }
+                    KeyCode::Char('n') => {
+                        app.switch_mode();
+                        break;
+                    }
+                    KeyCode::Char('e') => {
+                        if let Some(task) = app.selected_task() {
+                            *modal_state = TaskModalState::from_task(task);
+                            app.mode = AppMode::EditTask;
+                        }
+                        break;
+                    }
+                    KeyCode::Char('d') => {
+                        app.delete_task(db);
+                        break;
+                    }
+                    KeyCode::Char('b') => {
+                        app.mode = AppMode::Boards;
+                        break;
+                    }
+                    KeyCode::Char('t') => {
+                        app.cycle_tag_filter(db)?;
+                        break;
+                    }
+                    KeyCode::Char('p') => {
+                        app.cycle_priority(db)?;
+                        break;
+                    }
+                    KeyCode::Char('c') => {
+                        app.open_checklist(db)?;
+                        break;
+                    }
+                    KeyCode::Char('l') => {
+                        app.open_dependencies(db)?;
+                        break;
+                    }
+                    KeyCode::Char('w') => {
+                        app.mode = AppMode::Columns;
+                        break;
+                    }
+                    KeyCode::Char('h') => {
+                        app.open_history(db)?;
+                        break;
+                    }
+                    KeyCode::Char('s') => {
+                        app.toggle_timer(db)?;
+                        break;
+                    }
+                    KeyCode::Char('i') => {
+                        app.open_time_entries(db)?;
+                        break;
+                    }
+                    KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                        app.redo(db);
+                        break;
+                    }
+                    KeyCode::Char('r') => {
+                        app.open_time_report(db)?;
+                        break;
+                    }
+                    KeyCode::Char('u') => {
+                        app.undo(db);
+                        break;
+                    }
+                    KeyCode::Char('x') => {
+                        app.open_trash(db)?;
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::Columns => match key_event.code {
+                    KeyCode::Down => {
+                        if app.selected_column + 1 < app.columns.len() {
+                            app.selected_column += 1;
+                        }
+                        break;
+                    }
+                    KeyCode::Up => {
+                        app.selected_column = app.selected_column.saturating_sub(1);
+                        break;
+                    }
+                    KeyCode::Left => {
+                        app.shift_column(db, -1)?;
+                        break;
+                    }
+                    KeyCode::Right => {
+                        app.shift_column(db, 1)?;
+                        break;
+                    }
+                    KeyCode::Char('n') => {
+                        column_modal_state.clear();
+                        app.mode = AppMode::NewColumn;
+                        break;
+                    }
+                    KeyCode::Char('r') => {
+                        if let Some(column) = app.current_column() {
+                            *column_modal_state = ColumnModalState::from_column(column);
+                            app.mode = AppMode::EditColumn;
+                        }
+                        break;
+                    }
+                    KeyCode::Char('s') => {
+                        app.cycle_column_status(db)?;
+                        break;
+                    }
+                    KeyCode::Char('k') => {
+                        app.cycle_column_color(db)?;
+                        break;
+                    }
+                    KeyCode::Char('+') => {
+                        app.change_column_wip_limit(db, 1)?;
+                        break;
+                    }
+                    KeyCode::Char('-') => {
+                        app.change_column_wip_limit(db, -1)?;
+                        break;
+                    }
+                    KeyCode::Char('d') => {
+                        app.delete_selected_column(db)?;
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.selected_index = 0;
+                        app.switch_mode();
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::NewColumn | AppMode::EditColumn => match key_event.code {
+                    KeyCode::Enter => {
+                        let name = column_modal_state.name_in.trim().to_string();
+                        if name.is_empty() {
+                            continue;
+                        }
+                        if let Some(column) = &column_modal_state.editing {
+                            let mut column = column.clone();
+                            column.name = name;
+                            app.update_column(db, &column)?;
+                        } else {
+                            app.create_column(db, name)?;
+                        }
+                        app.switch_mode();
+                        column_modal_state.clear();
+                        break;
+                    }
+                    KeyCode::Backspace => {
+                        column_modal_state.name_in.pop();
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.switch_mode();
+                        column_modal_state.clear();
+                        break;
+                    }
+                    KeyCode::Char(ch) => {
+                        column_modal_state.name_in.push(ch);
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::TimeEntries => match key_event.code {
+                    KeyCode::Down => {
+                        app.select_next_time_entry();
+                        break;
+                    }
+                    KeyCode::Up => {
+                        app.select_prev_time_entry();
+                        break;
+                    }
+                    KeyCode::Char('+') => {
+                        app.adjust_time_entry(db, 5)?;
+                        break;
+                    }
+                    KeyCode::Char('-') => {
+                        app.adjust_time_entry(db, -5)?;
+                        break;
+                    }
+                    KeyCode::Char('s') => {
+                        app.toggle_timer(db)?;
+                        break;
+                    }
+                    KeyCode::Char('d') => {
+                        app.delete_time_entry(db)?;
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.close_time_entries(db);
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::Trash => match key_event.code {
+                    KeyCode::Down => {
+                        app.select_next_trash_task();
+                        break;
+                    }
+                    KeyCode::Up => {
+                        app.select_prev_trash_task();
+                        break;
+                    }
+                    KeyCode::Enter => {
+                        app.restore_trash_task(db)?;
+                        break;
+                    }
+                    KeyCode::Char('d') => {
+                        app.purge_trash_task(db)?;
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.close_trash(db);
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::TimeReport => match key_event.code {
+                    KeyCode::Down => {
+                        app.scroll_time_report_down();
+                        break;
+                    }
+                    KeyCode::Up => {
+                        app.scroll_time_report_up();
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.close_time_report();
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::History => match key_event.code {
+                    KeyCode::Down => {
+                        app.scroll_history_down();
+                        break;
+                    }
+                    KeyCode::Up => {
+                        app.scroll_history_up();
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.close_history();
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::Dependencies => match key_event.code {
+                    KeyCode::Down => {
+                        app.select_next_dependency();
+                        break;
+                    }
+                    KeyCode::Up => {
+                        app.select_prev_dependency();
+                        break;
+                    }
+                    KeyCode::Char(' ') => {
+                        app.toggle_dependency(db)?;
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.close_dependencies(db);
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::Checklist => match key_event.code {
+                    KeyCode::Down => {
+                        app.select_next_checklist_item();
+                        break;
+                    }
+                    KeyCode::Up => {
+                        app.select_prev_checklist_item();
+                        break;
+                    }
+                    KeyCode::Char(' ') => {
+                        app.toggle_checklist_item(db)?;
+                        break;
+                    }
+                    KeyCode::Char('n') => {
+                        checklist_modal_state.clear();
+                        app.mode = AppMode::NewChecklistItem;
+                        break;
+                    }
+                    KeyCode::Char('e') => {
+                        if let Some(item) = app.selected_checklist_item() {
+                            *checklist_modal_state = ChecklistModalState::from_item(item);
+                            app.mode = AppMode::EditChecklistItem;
+                        }
+                        break;
+                    }
+                    KeyCode::Char('d') => {
+                        app.delete_checklist_item(db)?;
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.close_checklist(db);
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::NewChecklistItem | AppMode::EditChecklistItem => match key_event.code {
+                    KeyCode::Enter => {
+                        let description = checklist_modal_state.description_in.trim().to_string();
+                        if description.is_empty() {
+                            continue;
+                        }
+                        if let Some(item) = &checklist_modal_state.editing {
+                            let mut item = item.clone();
+                            item.description = description;
+                            app.update_checklist_item(db, &item)?;
+                        } else {
+                            app.add_checklist_item(db, description)?;
+                        }
+                        app.switch_mode();
+                        checklist_modal_state.clear();
+                        break;
+                    }
+                    KeyCode::Backspace => {
+                        checklist_modal_state.description_in.pop();
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.switch_mode();
+                        checklist_modal_state.clear();
+                        break;
+                    }
+                    KeyCode::Char(ch) => {
+                        checklist_modal_state.description_in.push(ch);
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::Boards => match key_event.code {
+                    KeyCode::Down => {
+                        app.select_next_board();
+                        break;
+                    }
+                    KeyCode::Up => {
+                        app.select_prev_board();
+                        break;
+                    }
+                    KeyCode::Enter => {
+                        app.switch_to_selected_board(db);
+                        app.switch_mode();
+                        break;
+                    }
+                    KeyCode::Char('n') => {
+                        board_modal_state.clear();
+                        app.mode = AppMode::NewBoard;
+                        break;
+                    }
+                    KeyCode::Char('r') => {
+                        if let Some(board) = app.selected_board() {
+                            *board_modal_state = BoardModalState::from_board(board);
+                            app.mode = AppMode::EditBoard;
+                        }
+                        break;
+                    }
+                    KeyCode::Char('d') => {
+                        app.delete_selected_board(db)?;
+                        break;
+                    }
+                    KeyCode::Char('m') => {
+                        app.move_selected_task_to_board(db)?;
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.switch_mode();
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::NewBoard | AppMode::EditBoard => match key_event.code {
+                    KeyCode::Enter => {
+                        let name = board_modal_state.name_in.trim().to_string();
+                        if name.is_empty() {
+                            continue;
+                        }
+                        // The modal stays open with the error when the name is taken.
+                        if let Some(board) = &board_modal_state.editing {
+                            let mut board = board.clone();
+                            board.name = name;
+                            app.rename_board(db, &board)?;
+                        } else {
+                            app.create_board(db, &Board::new(name))?;
+                        }
+                        app.switch_mode();
+                        board_modal_state.clear();
+                        break;
+                    }
+                    KeyCode::Backspace => {
+                        board_modal_state.name_in.pop();
+                        break;
+                    }
+                    KeyCode::Esc => {
+                        app.switch_mode();
+                        board_modal_state.clear();
+                        break;
+                    }
+                    KeyCode::Char(ch) => {
+                        board_modal_state.name_in.push(ch);
+                        break;
+                    }
+                    _ => continue,
+                },
+                AppMode::NewTask | AppMode::EditTask => {
+                    if modal_state.calendar.is_some() {
+                        match key_event.code {
+                            KeyCode::Left => modal_state.move_calendar(-1),
+                            KeyCode::Right => modal_state.move_calendar(1),
+                            KeyCode::Up => modal_state.move_calendar(-7),
+                            KeyCode::Down => modal_state.move_calendar(7),
+                            KeyCode::PageUp => modal_state.shift_calendar_month(-1),
+                            KeyCode::PageDown => modal_state.shift_calendar_month(1),
+                            KeyCode::Enter => modal_state.pick_calendar_date(),
+                            KeyCode::Esc => modal_state.calendar = None,
+                            _ => continue,
+                        }
+                        break;
+                    }
+                    match (key_event.code, key_event.modifiers) {
+                        (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+                            // Invalid input stays in the modal, where it is
+                            // already flagged under the deadline field.
+                            let Ok(deadline) = modal_state.deadline() else {
+                                modal_state.focus = ModalField::Deadline;
+                                break;
+                            };
+                            // Likewise for a repeat rule that does not parse.
+                            let Ok(recurrence) = modal_state.recurrence() else {
+                                modal_state.focus = ModalField::Recurrence;
+                                break;
+                            };
+
+                            let tags = Task::parse_tags(modal_state.tags_in.as_str());
 
-        loop {
-            if let Event::Key(key_event) = event::read()? {
-                match app.mode {
-                    AppMode::Board => match key_event.code {
-                        KeyCode::Char('q') => {
-                            app.should_quit = true;
+                            if let Some(mut task) = modal_state.editing.take() {
+                                task.description = modal_state.description_in.as_str().to_string();
+                                task.deadline = deadline;
+                                task.tags = tags;
+                                task.priority = modal_state.priority;
+                                task.recurrence = recurrence;
+                                app.update_task(db, &task);
+                            } else {
+                                let mut task = Task::new(
+                                    modal_state.description_in.as_str().to_string(),
+                                    None,
+                                    deadline,
+                                    app.current_board,
+                                );
+                                task.tags = tags;
+                                task.priority = modal_state.priority;
+                                task.recurrence = recurrence;
+                                app.create_task(db, &task);
+                            }
+                            app.switch_mode();
+                            modal_state.clear();
                             break;
                         }
-                        KeyCode::Tab => {
-                            app.select_next_status();
+                        (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                            modal_state.priority = modal_state.priority.next();
                             break;
                         }
-                        KeyCode::Left => {
-                            app.move_task_to_column(&mut db, &app.selected_status.prev())?;
+                        (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
+                            modal_state.open_calendar();
                             break;
                         }
-                        KeyCode::Right => {
-                            app.move_task_to_column(&mut db, &app.selected_status.next())?;
+                        (KeyCode::Char('w'), KeyModifiers::CONTROL) => {
+                            modal_state.focused_input().delete_word();
                             break;
                         }
-                        KeyCode::Down => {
-                            app.select_next_task();
+                        (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                            modal_state.focused_input().delete_to_line_start();
                             break;
                         }
-                        KeyCode::Up => {
-                            app.select_prev_task();
+                        (KeyCode::Backspace, _) => {
+                            modal_state.focused_input().backspace();
                             break;
                         }
-                        KeyCode::Char('n') => {
-                            app.switch_mode();
+                        (KeyCode::Delete, _) => {
+                            modal_state.focused_input().delete();
                             break;
                         }
-                        KeyCode::Char('d') => {
-                            app.delete_task(&mut db);
+                        (KeyCode::Left, _) => {
+                            modal_state.focused_input().move_left();
                             break;
                         }
-                        _ => continue,
-                    },
-                    AppMode::NewTask => match (key_event.code, key_event.modifiers) {
-                        (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
-                            let deadline = crate::utils::str_to_local_dt(&modal_state.deadline_in);
-
-                            let task =
-                                Task::new(modal_state.description_in.to_string(), None, deadline);
-                            app.create_task(&mut db, &task);
-                            app.switch_mode();
-                            modal_state.clear();
+                        (KeyCode::Right, _) => {
+                            modal_state.focused_input().move_right();
                             break;
                         }
-                        (KeyCode::Backspace, _) => {
-                            if !modal_state.entering_deadline {
-                                modal_state.description_in.pop();
-                            } else {
-                                modal_state.deadline_in.pop();
-                            }
+                        (KeyCode::Up, _) => {
+                            modal_state.focused_input().move_up();
+                            break;
+                        }
+                        (KeyCode::Down, _) => {
+                            modal_state.focused_input().move_down();
+                            break;
+                        }
+                        (KeyCode::Home, _) => {
+                            modal_state.focused_input().move_home();
+                            break;
+                        }
+                        (KeyCode::End, _) => {
+                            modal_state.focused_input().move_end();
                             break;
                         }
                         (KeyCode::Esc, _) => {
@@ -110,35 +767,27 @@ fn main() -> Result<(), AppError> {
                             modal_state.clear();
                             break;
                         }
-                        (KeyCode::Char(_), _) => {
-                            if let Some(ch) = key_event.code.as_char() {
-                                if !modal_state.entering_deadline {
-                                    modal_state.description_in.push(ch);
-                                } else {
-                                    modal_state.deadline_in.push(ch);
-                                }
-                                break;
-                            }
+                        (KeyCode::Char(ch), modifiers)
+                            if !modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            modal_state.focused_input().insert(ch);
+                            break;
                         }
                         (KeyCode::Enter, _) => {
-                            if !modal_state.entering_deadline {
-                                modal_state.description_in.push('\n');
+                            if modal_state.focus == ModalField::Description {
+                                modal_state.description_in.insert('\n');
                             }
+                            break;
                         }
                         (KeyCode::Tab, _) => {
-                            modal_state.entering_deadline = !modal_state.entering_deadline;
+                            modal_state.focus = modal_state.focus.next();
+                            break;
                         }
                         _ => continue,
-                    },
+                    }
                 }
             }
         }
-
-        if app.should_quit {
-            break;
-        }
     }
-
-    disable_raw_mode()?;
     Ok(())
 }
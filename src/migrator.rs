@@ -2,14 +2,21 @@ use std::env;
 use std::fs;
 use std::path::PathBuf;
 
-pub struct Migrator;
+pub struct Migrator {
+    migrations_dir: PathBuf,
+}
 
 impl Migrator {
+    /// Reads the `migrations` directory next to the executable.
     pub fn new() -> Self {
-        Self
+        Self::in_dir(Self::app_root().join("migrations"))
+    }
+
+    pub fn in_dir(migrations_dir: PathBuf) -> Self {
+        Self { migrations_dir }
     }
 
-    fn app_root(&self) -> PathBuf {
+    fn app_root() -> PathBuf {
         let exe_path = env::current_exe().expect("Failed to get current exe path");
         exe_path
             .parent() // bin/
@@ -17,9 +24,10 @@ impl Migrator {
             .to_path_buf()
     }
 
-    pub fn get_migrations(&self) -> String {
-        let migrations_dir = self.app_root().join("migrations");
-        let mut result = String::new();
+    /// Returns `(name, sql)` pairs for every `.sql` file, ordered by file name.
+    pub fn get_migrations(&self) -> Vec<(String, String)> {
+        let migrations_dir = &self.migrations_dir;
+        let mut result = Vec::new();
 
         if !migrations_dir.exists() {
             panic!(
@@ -28,7 +36,7 @@ impl Migrator {
             );
         }
 
-        let mut entries: Vec<_> = fs::read_dir(&migrations_dir)
+        let mut entries: Vec<_> = fs::read_dir(migrations_dir)
             .expect("Failed to read migrations dir")
             .filter_map(|e| e.ok())
             .filter(|e| e.path().is_file())
@@ -42,8 +50,12 @@ impl Migrator {
             if path.extension().and_then(|s| s.to_str()) == Some("sql") {
                 let contents = fs::read_to_string(&path)
                     .unwrap_or_else(|_| panic!("Failed to read {:?}", path));
-                result.push_str(&contents);
-                result.push('\n');
+                let name = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default()
+                    .to_string();
+                result.push((name, contents));
             }
         }
 
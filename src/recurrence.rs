@@ -0,0 +1,205 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Local, Weekday};
+
+use crate::utils::{add_days, next_month_day_after, next_weekday_after};
+
+/// Longest interval of `every N days`, a hundred years.
+const MAX_DAYS: u32 = 36_500;
+
+/// Repeat rule of a recurring task.
+///
+/// Stored in the database as its `Display` form, which is also what the user
+/// types in the task modal: `daily`, `weekly mon,fri`, `monthly 15` or
+/// `every 3 days after done`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Recurrence {
+    Daily,
+    Weekly(Vec<Weekday>),
+    Monthly(u32),
+    AfterCompletion(u32),
+}
+
+impl Recurrence {
+    /// Deadline of the occurrence that follows one completed at `completed_at`.
+    ///
+    /// Calendar rules are scheduled from the previous deadline (or the completion
+    /// time when there was none) and rolled forward until they are in the future.
+    /// `None` when that is past the dates chrono can represent.
+    pub fn next_deadline(
+        &self,
+        deadline: Option<DateTime<Local>>,
+        completed_at: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        let base = deadline.unwrap_or(completed_at);
+        if let Recurrence::AfterCompletion(days) = self {
+            let completed_on = completed_at.date_naive().and_time(base.time());
+            let start = completed_on
+                .and_local_timezone(Local)
+                .earliest()
+                .unwrap_or(completed_at);
+            return add_days(start, *days as i64);
+        }
+
+        let mut next = self.step(base)?;
+        while next <= completed_at {
+            next = self.step(next)?;
+        }
+        Some(next)
+    }
+
+    fn step(&self, dt: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Recurrence::Daily => add_days(dt, 1),
+            Recurrence::Weekly(weekdays) => next_weekday_after(dt, weekdays),
+            Recurrence::Monthly(day) => next_month_day_after(dt, *day),
+            Recurrence::AfterCompletion(days) => add_days(dt, *days as i64),
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly(weekdays) => {
+                let days: Vec<String> = weekdays
+                    .iter()
+                    .map(|d| d.to_string().to_lowercase())
+                    .collect();
+                write!(f, "weekly {}", days.join(","))
+            }
+            Recurrence::Monthly(day) => write!(f, "monthly {}", day),
+            Recurrence::AfterCompletion(days) => write!(f, "every {} days after done", days),
+        }
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let words: Vec<&str> = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        match words.as_slice() {
+            ["daily"] | ["every", "day"] => Ok(Recurrence::Daily),
+            ["weekly", days @ ..] if !days.is_empty() => {
+                let mut weekdays = days
+                    .iter()
+                    .map(|d| Weekday::from_str(d).map_err(|_| format!("unknown weekday: {d}")))
+                    .collect::<Result<Vec<Weekday>, String>>()?;
+                weekdays.sort_by_key(|d| d.num_days_from_monday());
+                weekdays.dedup();
+                Ok(Recurrence::Weekly(weekdays))
+            }
+            ["monthly", day] => match day.parse::<u32>() {
+                Ok(day @ 1..=31) => Ok(Recurrence::Monthly(day)),
+                _ => Err(format!("invalid day of month: {day}")),
+            },
+            ["every", n, "days", rest @ ..] if rest.is_empty() || rest == ["after", "done"] => {
+                match n.parse::<u32>() {
+                    Ok(n @ 1..=MAX_DAYS) => Ok(Recurrence::AfterCompletion(n)),
+                    _ => Err(format!("number of days must be 1 to {MAX_DAYS}: {n}")),
+                }
+            }
+            _ => Err(format!("unknown repeat rule: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for rule in [
+            Recurrence::Daily,
+            Recurrence::Weekly(vec![Weekday::Mon, Weekday::Fri]),
+            Recurrence::Monthly(31),
+            Recurrence::AfterCompletion(3),
+            Recurrence::AfterCompletion(MAX_DAYS),
+        ] {
+            assert_eq!(rule.to_string().parse::<Recurrence>(), Ok(rule));
+        }
+    }
+
+    #[test]
+    fn parses_loose_input() {
+        assert_eq!(
+            "Weekly fri, mon, fri".parse::<Recurrence>(),
+            Ok(Recurrence::Weekly(vec![Weekday::Mon, Weekday::Fri]))
+        );
+        assert_eq!(
+            "every 3 days".parse::<Recurrence>(),
+            Ok(Recurrence::AfterCompletion(3))
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_numbers() {
+        for rule in [
+            "monthly 0",
+            "monthly 32",
+            "every 0 days",
+            "every 36501 days",
+            "every 100000000 days",
+            "every -1 days",
+        ] {
+            assert!(rule.parse::<Recurrence>().is_err(), "{rule}");
+        }
+    }
+
+    #[test]
+    fn rolls_calendar_rules_past_completion() {
+        let deadline = Some(at(2026, 5, 31, 9));
+        let completed_at = at(2026, 7, 8, 12);
+        assert_eq!(
+            Recurrence::Monthly(31).next_deadline(deadline, completed_at),
+            Some(at(2026, 7, 31, 9))
+        );
+        assert_eq!(
+            Recurrence::Daily.next_deadline(deadline, completed_at),
+            Some(at(2026, 7, 9, 9))
+        );
+        // 2026-07-08 is a Wednesday.
+        assert_eq!(
+            Recurrence::Weekly(vec![Weekday::Mon]).next_deadline(deadline, completed_at),
+            Some(at(2026, 7, 13, 9))
+        );
+    }
+
+    #[test]
+    fn counts_days_after_completion() {
+        let completed_at = at(2026, 7, 8, 12);
+        assert_eq!(
+            Recurrence::AfterCompletion(3).next_deadline(Some(at(2026, 6, 1, 9)), completed_at),
+            Some(at(2026, 7, 11, 9))
+        );
+    }
+
+    #[test]
+    fn out_of_range_deadline_is_none() {
+        let completed_at = at(2026, 7, 8, 12);
+        assert_eq!(
+            Recurrence::AfterCompletion(u32::MAX).next_deadline(None, completed_at),
+            None
+        );
+        let last = chrono::NaiveDate::MAX
+            .and_hms_opt(12, 0, 0)
+            .and_then(|dt| dt.and_local_timezone(Local).earliest())
+            .unwrap();
+        assert_eq!(Recurrence::Daily.next_deadline(None, last), None);
+        assert_eq!(Recurrence::Monthly(1).next_deadline(None, last), None);
+    }
+}
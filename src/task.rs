@@ -1,8 +1,23 @@
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Duration, Local};
+
+use crate::recurrence::Recurrence;
+use serde::{Deserialize, Serialize};
 use strum::{EnumIter, EnumMessage};
 use strum_macros::{Display, EnumString};
 
-#[derive(Display, EnumString, EnumMessage, Debug, Clone, Copy, PartialEq, EnumIter)]
+#[derive(
+    Display,
+    EnumString,
+    EnumMessage,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
 #[repr(u8)]
 pub enum Status {
     #[strum(serialize = "backlog", message = "BACKLOG")]
@@ -17,14 +32,69 @@ pub enum Status {
     Archived = 4,
 }
 
+#[derive(
+    Display,
+    EnumString,
+    EnumMessage,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum Priority {
+    #[strum(serialize = "none", message = "NONE")]
+    None = 0,
+    #[strum(serialize = "low", message = "LOW")]
+    Low = 1,
+    #[strum(serialize = "medium", message = "MEDIUM")]
+    Medium = 2,
+    #[strum(serialize = "high", message = "HIGH")]
+    High = 3,
+    #[strum(serialize = "urgent", message = "URGENT")]
+    Urgent = 4,
+}
+
 #[derive(Debug, Clone)]
 pub struct Task {
     pub id: Option<i64>,
+    pub board_id: i64,
+    /// Column on the board; `None` lets the database pick the first column
+    /// with the task's `status`.
+    pub column_id: Option<i64>,
+    /// Workflow stage, always equal to the status of the task's column.
     pub status: Status,
+    /// Order inside the column; `None` places the task at the configured edge
+    /// of its column.
+    pub position: Option<i64>,
+    /// Set once the user reordered the card by hand. Such cards come first in
+    /// their priority group by `position`; the others follow by deadline.
+    pub ordered_manually: bool,
+    pub priority: Priority,
     pub description: String,
     pub created_at: DateTime<Local>,
     pub updated_at: DateTime<Local>,
     pub deadline: Option<DateTime<Local>>,
+    pub tags: Vec<String>,
+    pub recurrence: Option<Recurrence>,
+    /// Checklist progress, read-only: items are managed through `ChecklistItem`.
+    pub checklist_done: usize,
+    pub checklist_total: usize,
+    /// Ids of unfinished tasks blocking this one, read-only like the checklist.
+    pub blocked_by: Vec<i64>,
+    /// Set while the task is in the trash.
+    pub deleted_at: Option<DateTime<Local>>,
+    /// Time of finished entries and the start of the running timer, read-only:
+    /// entries are managed through `TimeEntry`.
+    pub time_spent: Duration,
+    pub timer_started_at: Option<DateTime<Local>>,
 }
 
 impl Task {
@@ -32,18 +102,89 @@ impl Task {
         description: String,
         status: Option<Status>,
         deadline: Option<DateTime<Local>>,
+        board_id: i64,
     ) -> Task {
         Task {
             id: None,
+            board_id,
+            column_id: None,
+            position: None,
+            ordered_manually: false,
             status: status.unwrap_or(Status::Backlog),
+            priority: Priority::None,
             description,
             created_at: Local::now(),
             updated_at: Local::now(),
             deadline,
+            tags: Vec::new(),
+            recurrence: None,
+            checklist_done: 0,
+            checklist_total: 0,
+            blocked_by: Vec::new(),
+            deleted_at: None,
+            time_spent: Duration::zero(),
+            timer_started_at: None,
+        }
+    }
+
+    /// The next occurrence of a recurring task completed at `completed_at`.
+    pub fn next_occurrence(&self, completed_at: DateTime<Local>) -> Option<Task> {
+        let recurrence = self.recurrence.as_ref()?;
+        let mut task = Task::new(
+            self.description.to_string(),
+            None,
+            Some(recurrence.next_deadline(self.deadline, completed_at)?),
+            self.board_id,
+        );
+        task.priority = self.priority;
+        task.tags = self.tags.clone();
+        task.recurrence = self.recurrence.clone();
+        Some(task)
+    }
+
+    /// Total tracked time, including the running timer up to `now`.
+    pub fn tracked_time(&self, now: DateTime<Local>) -> Duration {
+        self.time_spent + self.timer_started_at.map_or(Duration::zero(), |s| now - s)
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        !self.blocked_by.is_empty()
+    }
+
+    /// Splits free-form input such as `"work, urgent home"` into unique tags,
+    /// sorted as the database loads them. Tags keep their case, but ones that
+    /// differ only in case are the same tag: the first spelling is kept here,
+    /// and the one saved first in the database.
+    pub fn parse_tags(s: &str) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(|t| t.trim_start_matches('#'))
+            .filter(|t| !t.is_empty())
+        {
+            if !tags.iter().any(|t| same_tag(t, tag)) {
+                tags.push(tag.to_string());
+            }
         }
+        tags.sort();
+        tags
+    }
+
+    /// Whether both have the same tags, whatever their spelling.
+    pub fn same_tags(&self, other: &Task) -> bool {
+        self.tags.len() == other.tags.len()
+            && self
+                .tags
+                .iter()
+                .all(|tag| other.tags.iter().any(|t| same_tag(t, tag)))
     }
 }
 
+/// Whether two tags are the same, ignoring case.
+pub fn same_tag(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
 impl Status {
     const VARIANTS: [Status; 5] = [
         Status::Backlog,
@@ -58,13 +199,55 @@ impl Status {
         let next_idx = (curr_idx + 1) % Self::VARIANTS.len();
         Self::VARIANTS[next_idx]
     }
+}
 
-    pub fn prev(self) -> Self {
-        let curr_idx = self as usize;
-        if curr_idx == 0 {
-            return Self::VARIANTS[Self::VARIANTS.len() - 1];
-        }
-        let next_idx = (curr_idx - 1) % Self::VARIANTS.len();
+impl Priority {
+    const VARIANTS: [Priority; 5] = [
+        Priority::None,
+        Priority::Low,
+        Priority::Medium,
+        Priority::High,
+        Priority::Urgent,
+    ];
+
+    pub fn next(self) -> Self {
+        let next_idx = (self as usize + 1) % Self::VARIANTS.len();
         Self::VARIANTS[next_idx]
     }
+
+    /// Short marker drawn in front of the card description.
+    pub fn marker(self) -> &'static str {
+        match self {
+            Priority::None => "",
+            Priority::Low => "!",
+            Priority::Medium => "!!",
+            Priority::High => "!!!",
+            Priority::Urgent => "!!!!",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tags_keeping_their_case() {
+        assert_eq!(
+            Task::parse_tags("#Work, urgent  home,work #URGENT"),
+            vec!["Work", "home", "urgent"]
+        );
+        assert!(Task::parse_tags(" ,# ").is_empty());
+    }
+
+    #[test]
+    fn compares_tags_ignoring_case() {
+        let mut a = Task::new("a".to_string(), None, None, 1);
+        let mut b = a.clone();
+        a.tags = Task::parse_tags("Work home");
+        b.tags = Task::parse_tags("home work");
+        assert!(a.same_tags(&b));
+        b.tags = Task::parse_tags("home");
+        assert!(!a.same_tags(&b));
+    }
 }
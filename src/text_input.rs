@@ -0,0 +1,201 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Editable text with a cursor, as used by the fields of the task modal.
+///
+/// The cursor is a byte offset into `text` that always sits on a char
+/// boundary, so multi-byte input such as Cyrillic is edited char by char.
+/// Columns are display widths, so a CJK char takes two of them.
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    /// Input holding `text` with the cursor at its end.
+    pub fn from(text: &str) -> TextInput {
+        TextInput {
+            text: text.to_string(),
+            cursor: text.len(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Replaces the whole text and moves the cursor to its end.
+    pub fn set(&mut self, text: &str) {
+        *self = TextInput::from(text);
+    }
+
+    pub fn clear(&mut self) {
+        *self = TextInput::default();
+    }
+
+    pub fn insert(&mut self, ch: char) {
+        self.text.insert(self.cursor, ch);
+        self.cursor += ch.len_utf8();
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(start) = self.prev_boundary() {
+            self.text.replace_range(start..self.cursor, "");
+            self.cursor = start;
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if let Some(end) = self.next_boundary() {
+            self.text.replace_range(self.cursor..end, "");
+        }
+    }
+
+    /// Deletes the word before the cursor together with the spaces between it
+    /// and the cursor (Ctrl+W).
+    pub fn delete_word(&mut self) {
+        let before = &self.text[..self.cursor];
+        let trimmed = before.trim_end_matches(|c: char| c.is_whitespace() && c != '\n');
+        let start = trimmed
+            .rfind(char::is_whitespace)
+            .map(|i| i + trimmed[i..].chars().next().map_or(0, char::len_utf8))
+            .unwrap_or(0);
+        let start = if start == self.cursor {
+            self.prev_boundary().unwrap_or(start)
+        } else {
+            start
+        };
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Deletes from the start of the line up to the cursor (Ctrl+U).
+    pub fn delete_to_line_start(&mut self) {
+        let start = self.line_start();
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(prev) = self.prev_boundary() {
+            self.cursor = prev;
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(next) = self.next_boundary() {
+            self.cursor = next;
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = self.line_start();
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.line_end();
+    }
+
+    /// Moves to the same column of the previous line, or the start of the text.
+    pub fn move_up(&mut self) {
+        let start = self.line_start();
+        if start == 0 {
+            self.cursor = 0;
+            return;
+        }
+        let column = self.text[start..self.cursor].width();
+        self.cursor = start - 1;
+        let prev_start = self.line_start();
+        self.cursor = advance(&self.text, prev_start, column, start - 1);
+    }
+
+    /// Moves to the same column of the next line, or the end of the text.
+    pub fn move_down(&mut self) {
+        let end = self.line_end();
+        if end == self.text.len() {
+            self.cursor = end;
+            return;
+        }
+        let column = self.text[self.line_start()..self.cursor].width();
+        self.cursor = end + 1;
+        let next_end = self.line_end();
+        self.cursor = advance(&self.text, end + 1, column, next_end);
+    }
+
+    /// Splits the text into rows at most `width` columns wide, breaking at
+    /// newlines, and returns them with the cursor's (row, column).
+    pub fn wrap(&self, width: usize) -> (Vec<&str>, (usize, usize)) {
+        let width = width.max(1);
+        let mut rows = Vec::new();
+        let mut cursor = (0, 0);
+        let mut offset = 0;
+        for line in self.text.split('\n') {
+            let mut start = 0;
+            let mut count = 0;
+            for (i, ch) in line.char_indices() {
+                let ch_width = ch.width().unwrap_or(0);
+                if count + ch_width > width && count > 0 {
+                    rows.push(&line[start..i]);
+                    start = i;
+                    count = 0;
+                }
+                if offset + i == self.cursor {
+                    cursor = (rows.len(), count);
+                }
+                count += ch_width;
+            }
+            if offset + line.len() == self.cursor {
+                // A cursor after a full row waits at the start of the next one.
+                cursor = if count >= width {
+                    (rows.len() + 1, 0)
+                } else {
+                    (rows.len(), count)
+                };
+            }
+            rows.push(&line[start..]);
+            offset += line.len() + 1;
+        }
+        (rows, cursor)
+    }
+
+    fn prev_boundary(&self) -> Option<usize> {
+        self.text[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map(|(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.text[self.cursor..]
+            .chars()
+            .next()
+            .map(|c| self.cursor + c.len_utf8())
+    }
+
+    fn line_start(&self) -> usize {
+        self.text[..self.cursor].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self) -> usize {
+        self.text[self.cursor..]
+            .find('\n')
+            .map_or(self.text.len(), |i| self.cursor + i)
+    }
+}
+
+/// Byte offset `columns` columns after `from`, stopping at `limit` or before
+/// a char that would go past the column.
+fn advance(text: &str, from: usize, columns: usize, limit: usize) -> usize {
+    let mut width = 0;
+    for (i, ch) in text[from..limit].char_indices() {
+        width += ch.width().unwrap_or(0);
+        if width > columns {
+            return from + i;
+        }
+    }
+    limit
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Duration, Local, NaiveDate};
+
+/// One stretch of work on a task. A running timer has no `ended_at`.
+#[derive(Debug, Clone)]
+pub struct TimeEntry {
+    pub id: Option<i64>,
+    pub task_id: i64,
+    pub started_at: DateTime<Local>,
+    pub ended_at: Option<DateTime<Local>>,
+}
+
+impl TimeEntry {
+    pub fn is_running(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// Length of the entry; a running entry counts up to `now`.
+    pub fn duration(&self, now: DateTime<Local>) -> Duration {
+        self.ended_at.unwrap_or(now) - self.started_at
+    }
+}
+
+/// Tracked time of one task on one day, as listed in the time report.
+#[derive(Debug, Clone)]
+pub struct TimeSummary {
+    pub day: NaiveDate,
+    pub task_id: i64,
+    pub description: String,
+    pub duration: Duration,
+}
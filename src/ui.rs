@@ -1,35 +1,550 @@
-use std::slice::Iter;
+use std::{slice::Iter, str::FromStr};
 
-use crate::app::{AppControl, AppState, BOARD_CONTROLS, TASK_MODAL_CONTROLS, TaskModalState};
+use crate::app::{
+    AppControl, AppState, BOARD_CONTROLS, BOARD_MODAL_CONTROLS, BOARDS_CONTROLS, BoardModalState,
+    CALENDAR_CONTROLS, CHECKLIST_CONTROLS, CHECKLIST_MODAL_CONTROLS, COLUMN_MODAL_CONTROLS,
+    COLUMNS_CONTROLS, ChecklistModalState, ColumnModalState, DEPENDENCIES_CONTROLS,
+    EDIT_TASK_MODAL_CONTROLS, HISTORY_CONTROLS, ModalField, TASK_MODAL_CONTROLS,
+    TIME_ENTRIES_CONTROLS, TIME_REPORT_CONTROLS, TRASH_CONTROLS, TaskModalState,
+};
+use crate::column::Column;
+use crate::task::Priority;
+use crate::text_input::TextInput;
+use crate::utils::format_duration;
+use chrono::{Datelike, Duration, Local, NaiveDate};
 use ratatui::{
     Frame,
     layout::*,
     style::{Color, Style, Stylize},
-    text::{Line, Span},
+    text::{Line, Span, Text},
     widgets::*,
 };
-use strum::{EnumMessage, IntoEnumIterator};
+use strum::EnumMessage;
 
 pub fn render_board(frame: &mut Frame, app: &AppState) {
     let global_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Percentage(10),
+        ]);
     let global_chunks = global_layout.split(frame.area());
+    let header = match &app.tag_filter {
+        Some(tag) => format!("Board: {} | Filter: #{}", app.current_board_name(), tag),
+        None => format!("Board: {}", app.current_board_name()),
+    };
+    let mut header_spans = vec![Span::styled(header, Style::new().green().bold())];
+    if let Some(message) = &app.status_message {
+        header_spans.push(Span::styled(
+            format!(" | {}", message),
+            Style::new().red().bold(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(header_spans)), global_chunks[0]);
+    let columns_count = app.columns.len().max(1) as u32;
     let table_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(20), // Backlog
-            Constraint::Percentage(20), // Today
-            Constraint::Percentage(20), // InProgress
-            Constraint::Percentage(20), // Done
-            Constraint::Percentage(20), // Archived
-        ])
-        .split(global_chunks[0]);
-    for (i, s) in crate::task::Status::iter().enumerate() {
-        let status = &s;
-        frame.render_widget(build_column_for(app, status), table_chunks[i]);
+        .constraints(
+            app.columns
+                .iter()
+                .map(|_| Constraint::Ratio(1, columns_count)),
+        )
+        .split(global_chunks[1]);
+    for (i, column) in app.columns.iter().enumerate() {
+        frame.render_widget(build_column_for(app, i, column), table_chunks[i]);
+    }
+    frame.render_widget(build_controls_row(BOARD_CONTROLS.iter()), global_chunks[2]);
+}
+
+pub fn render_boards(frame: &mut Frame, app: &AppState) {
+    let global_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+    let global_chunks = global_layout.split(frame.area());
+
+    let rect = Rect::new(
+        global_chunks[0].width / 4,
+        global_chunks[0].height / 4,
+        global_chunks[0].width / 2,
+        global_chunks[0].height / 2,
+    );
+    let boards: Vec<ListItem> = app
+        .boards
+        .iter()
+        .enumerate()
+        .map(|(i, board)| {
+            let selected = app.selected_board_index == i;
+            let current = board.id == Some(app.current_board);
+            let label = format!(
+                "{}{}{}",
+                if selected { "> " } else { "" },
+                board.name,
+                if current { " (current)" } else { "" }
+            );
+            if selected {
+                ListItem::new(label).style(Style::new().blue().italic())
+            } else {
+                ListItem::new(label).style(Style::new().green().bold())
+            }
+        })
+        .collect();
+    let list = List::new(boards).block(
+        Block::default()
+            .title("Boards")
+            .borders(Borders::ALL)
+            .border_style(Style::new().green()),
+    );
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(rect);
+    frame.render_widget(list, layout[0]);
+    if let Some(message) = &app.status_message {
+        frame.render_widget(
+            Paragraph::new(message.to_string()).style(Style::new().red().bold()),
+            layout[1],
+        );
     }
-    frame.render_widget(build_controls_row(BOARD_CONTROLS.iter()), global_chunks[1]);
+    frame.render_widget(build_controls_row(BOARDS_CONTROLS.iter()), global_chunks[1]);
+}
+
+pub fn render_board_modal(frame: &mut Frame, state: &BoardModalState, error: Option<&str>) {
+    render_line_modal(
+        frame,
+        if state.is_editing() {
+            "Rename Board"
+        } else {
+            "Create New Board"
+        },
+        "Enter board name...",
+        &state.name_in,
+        BOARD_MODAL_CONTROLS.iter(),
+        error,
+    );
+}
+
+pub fn render_checklist(frame: &mut Frame, app: &AppState) {
+    let global_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+    let global_chunks = global_layout.split(frame.area());
+
+    let rect = Rect::new(
+        global_chunks[0].width / 4,
+        global_chunks[0].height / 4,
+        global_chunks[0].width / 2,
+        global_chunks[0].height / 2,
+    );
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(rect);
+
+    let items: Vec<ListItem> = app
+        .checklist
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let selected = app.selected_checklist_index == i;
+            let label = format!(
+                "{}[{}] {}",
+                if selected { "> " } else { "" },
+                if item.done { "x" } else { " " },
+                item.description
+            );
+            if selected {
+                ListItem::new(label).style(Style::new().blue().italic())
+            } else {
+                ListItem::new(label).style(Style::new().green().bold())
+            }
+        })
+        .collect();
+    let title = app
+        .checklist_task
+        .as_ref()
+        .map(|t| format!("Checklist: {}", t.description.lines().next().unwrap_or("")))
+        .unwrap_or_default();
+    let list = List::new(items).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::new().green()),
+    );
+
+    let done = app.checklist.iter().filter(|i| i.done).count();
+    let total = app.checklist.len();
+    let gauge = Gauge::default()
+        .gauge_style(Style::new().green())
+        .ratio(if total == 0 {
+            0.0
+        } else {
+            done as f64 / total as f64
+        })
+        .label(format!("{}/{}", done, total));
+
+    frame.render_widget(list, layout[0]);
+    frame.render_widget(gauge, layout[1]);
+    frame.render_widget(
+        build_controls_row(CHECKLIST_CONTROLS.iter()),
+        global_chunks[1],
+    );
+}
+
+pub fn render_dependencies(frame: &mut Frame, app: &AppState) {
+    let global_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+    let global_chunks = global_layout.split(frame.area());
+
+    let rect = Rect::new(
+        global_chunks[0].width / 4,
+        global_chunks[0].height / 4,
+        global_chunks[0].width / 2,
+        global_chunks[0].height / 2,
+    );
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(rect);
+
+    let items: Vec<ListItem> = app
+        .dependency_candidates()
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let selected = app.selected_dependency_index == i;
+            let blocking = task.id.is_some_and(|id| app.dependencies.contains(&id));
+            let label = format!(
+                "{}[{}] #{} {} ({})",
+                if selected { "> " } else { "" },
+                if blocking { "x" } else { " " },
+                task.id.unwrap_or_default(),
+                task.description.lines().next().unwrap_or(""),
+                task.status.get_message().unwrap()
+            );
+            if selected {
+                ListItem::new(label).style(Style::new().blue().italic())
+            } else {
+                ListItem::new(label).style(Style::new().green().bold())
+            }
+        })
+        .collect();
+    let title = app
+        .dependencies_task
+        .as_ref()
+        .map(|t| {
+            format!(
+                "#{} {} is blocked by",
+                t.id.unwrap_or_default(),
+                t.description.lines().next().unwrap_or("")
+            )
+        })
+        .unwrap_or_default();
+    let list = List::new(items).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::new().green()),
+    );
+
+    frame.render_widget(list, layout[0]);
+    if let Some(message) = &app.status_message {
+        frame.render_widget(
+            Paragraph::new(message.to_string()).style(Style::new().red().bold()),
+            layout[1],
+        );
+    }
+    frame.render_widget(
+        build_controls_row(DEPENDENCIES_CONTROLS.iter()),
+        global_chunks[1],
+    );
+}
+
+pub fn render_history(frame: &mut Frame, app: &AppState) {
+    let global_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+    let global_chunks = global_layout.split(frame.area());
+
+    let rect = Rect::new(
+        global_chunks[0].width / 4,
+        global_chunks[0].height / 4,
+        global_chunks[0].width / 2,
+        global_chunks[0].height / 2,
+    );
+
+    let lines: Vec<Line> = app
+        .history
+        .iter()
+        .map(|event| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", event.created_at.format("%d/%m/%Y %H:%M")),
+                    Style::new().gray(),
+                ),
+                Span::styled(format!("{}: ", event.title()), Style::new().green().bold()),
+                Span::raw(event.summary()),
+            ])
+        })
+        .collect();
+    let title = app
+        .history_task
+        .as_ref()
+        .map(|t| {
+            format!(
+                "History of #{} {}",
+                t.id.unwrap_or_default(),
+                t.description.lines().next().unwrap_or("")
+            )
+        })
+        .unwrap_or_default();
+    let history = Paragraph::new(lines).scroll((app.history_scroll, 0)).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::new().green()),
+    );
+
+    frame.render_widget(history, rect);
+    frame.render_widget(
+        build_controls_row(HISTORY_CONTROLS.iter()),
+        global_chunks[1],
+    );
+}
+
+pub fn render_time_entries(frame: &mut Frame, app: &AppState) {
+    let global_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+    let global_chunks = global_layout.split(frame.area());
+
+    let rect = Rect::new(
+        global_chunks[0].width / 4,
+        global_chunks[0].height / 4,
+        global_chunks[0].width / 2,
+        global_chunks[0].height / 2,
+    );
+
+    let now = Local::now();
+    let dt_fmt = "%d/%m/%Y %H:%M";
+    let items: Vec<ListItem> = app
+        .time_entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let selected = app.selected_time_entry_index == i;
+            let label = format!(
+                "{}{} - {} ({})",
+                if selected { "> " } else { "" },
+                entry.started_at.format(dt_fmt),
+                entry
+                    .ended_at
+                    .map(|dt| dt.format(dt_fmt).to_string())
+                    .unwrap_or("running".to_string()),
+                format_duration(entry.duration(now))
+            );
+            if selected {
+                ListItem::new(label).style(Style::new().blue().italic())
+            } else {
+                ListItem::new(label).style(Style::new().green().bold())
+            }
+        })
+        .collect();
+    let total = app
+        .time_entries
+        .iter()
+        .fold(Duration::zero(), |acc, e| acc + e.duration(now));
+    let title = app
+        .time_task
+        .as_ref()
+        .map(|t| {
+            format!(
+                "Time of #{} {} - {}",
+                t.id.unwrap_or_default(),
+                t.description.lines().next().unwrap_or(""),
+                format_duration(total)
+            )
+        })
+        .unwrap_or_default();
+    let list = List::new(items).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::new().green()),
+    );
+
+    frame.render_widget(list, rect);
+    frame.render_widget(
+        build_controls_row(TIME_ENTRIES_CONTROLS.iter()),
+        global_chunks[1],
+    );
+}
+
+pub fn render_time_report(frame: &mut Frame, app: &AppState) {
+    let global_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+    let global_chunks = global_layout.split(frame.area());
+
+    let rect = Rect::new(
+        global_chunks[0].width / 4,
+        global_chunks[0].height / 4,
+        global_chunks[0].width / 2,
+        global_chunks[0].height / 2,
+    );
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, summary) in app.time_report.iter().enumerate() {
+        if i == 0 || app.time_report[i - 1].day != summary.day {
+            let day_total = app
+                .time_report
+                .iter()
+                .filter(|s| s.day == summary.day)
+                .fold(Duration::zero(), |acc, s| acc + s.duration);
+            lines.push(Line::styled(
+                format!(
+                    "{} - {}",
+                    summary.day.format("%d/%m/%Y"),
+                    format_duration(day_total)
+                ),
+                Style::new().blue().bold(),
+            ));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:>8} ", format_duration(summary.duration)),
+                Style::new().magenta(),
+            ),
+            Span::styled(
+                format!(
+                    "#{} {}",
+                    summary.task_id,
+                    summary.description.lines().next().unwrap_or("")
+                ),
+                Style::new().green(),
+            ),
+        ]));
+    }
+    let report = Paragraph::new(lines)
+        .scroll((app.time_report_scroll, 0))
+        .block(
+            Block::default()
+                .title(format!("Time report: {}", app.current_board_name()))
+                .borders(Borders::ALL)
+                .border_style(Style::new().green()),
+        );
+
+    frame.render_widget(report, rect);
+    frame.render_widget(
+        build_controls_row(TIME_REPORT_CONTROLS.iter()),
+        global_chunks[1],
+    );
+}
+
+pub fn render_trash(frame: &mut Frame, app: &AppState) {
+    let global_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+    let global_chunks = global_layout.split(frame.area());
+
+    let rect = Rect::new(
+        global_chunks[0].width / 4,
+        global_chunks[0].height / 4,
+        global_chunks[0].width / 2,
+        global_chunks[0].height / 2,
+    );
+
+    let items: Vec<ListItem> = app
+        .trash
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let selected = app.selected_trash_index == i;
+            let label = format!(
+                "{}#{} {} (deleted {})",
+                if selected { "> " } else { "" },
+                task.id.unwrap_or_default(),
+                task.description.lines().next().unwrap_or(""),
+                task.deleted_at
+                    .map(|d| d.format("%d/%m/%Y %H:%M").to_string())
+                    .unwrap_or_default()
+            );
+            if selected {
+                ListItem::new(label).style(Style::new().blue().italic())
+            } else {
+                ListItem::new(label).style(Style::new().green().bold())
+            }
+        })
+        .collect();
+    let list = List::new(items).block(
+        Block::default()
+            .title(format!("Trash: {}", app.current_board_name()))
+            .borders(Borders::ALL)
+            .border_style(Style::new().green()),
+    );
+
+    frame.render_widget(list, rect);
+    frame.render_widget(build_controls_row(TRASH_CONTROLS.iter()), global_chunks[1]);
+}
+
+pub fn render_checklist_modal(frame: &mut Frame, state: &ChecklistModalState, error: Option<&str>) {
+    render_line_modal(
+        frame,
+        if state.is_editing() {
+            "Edit Checklist Item"
+        } else {
+            "Add Checklist Item"
+        },
+        "Enter item description...",
+        &state.description_in,
+        CHECKLIST_MODAL_CONTROLS.iter(),
+        error,
+    );
+}
+
+/// Renders a centered single-line input with the given controls row.
+fn render_line_modal(
+    frame: &mut Frame,
+    title: &str,
+    placeholder: &str,
+    value: &str,
+    controls: Iter<AppControl>,
+    error: Option<&str>,
+) {
+    let global_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+    let global_chunks = global_layout.split(frame.area());
+
+    let block = Block::default()
+        .title(title.to_string())
+        .borders(Borders::ALL)
+        .border_style(Style::new().green());
+    let rect = Rect::new(
+        global_chunks[0].width / 4,
+        (global_chunks[0].height / 2).saturating_sub(1),
+        global_chunks[0].width / 2,
+        3,
+    );
+    let text = Paragraph::new(if value.is_empty() {
+        placeholder.to_string()
+    } else {
+        value.to_string()
+    })
+    .style(Style::new().white().bold())
+    .block(block);
+
+    frame.render_widget(text, rect);
+    if let Some(error) = error {
+        let below =
+            Rect::new(rect.x, rect.y + rect.height, rect.width, 1).intersection(global_chunks[0]);
+        frame.render_widget(
+            Paragraph::new(error.to_string()).style(Style::new().red().bold()),
+            below,
+        );
+    }
+    frame.render_widget(build_controls_row(controls), global_chunks[1]);
 }
 
 fn build_controls_row(controls: Iter<AppControl>) -> Paragraph<'static> {
@@ -49,24 +564,19 @@ fn build_controls_row(controls: Iter<AppControl>) -> Paragraph<'static> {
         .centered()
 }
 
-fn build_column_for<'a>(app: &'a AppState, status: &crate::task::Status) -> List<'a> {
+fn build_column_for<'a>(app: &'a AppState, index: usize, column: &Column) -> List<'a> {
+    let is_current = app.selected_column == index;
     let tasks: Vec<ListItem> = app
-        .tasks_for_status(status)
+        .tasks_for_column(column)
         .iter()
         .enumerate()
         .map(|task| {
-            let selected = app.selected_index == task.0 && app.selected_status == task.1.status;
+            let selected = is_current && app.selected_index == task.0;
 
             let dt_fmt = "%d/%m/%Y %H:%M";
 
             let fmt_data = format!(
-                "{}{}\nCreatedAt: {}\nUpdated At: {}\n{}\n",
-                if selected {
-                    "> ".to_string()
-                } else {
-                    String::new()
-                },
-                task.1.description,
+                "CreatedAt: {}\nUpdated At: {}\n{}\n",
                 &task.1.created_at.format(dt_fmt),
                 &task.1.updated_at.format(dt_fmt),
                 if let Some(deadline_str) = task.1.deadline {
@@ -76,25 +586,233 @@ fn build_column_for<'a>(app: &'a AppState, status: &crate::task::Status) -> List
                 }
             );
 
+            let mut text = Text::from(task.1.description.to_string());
+            if let Some(first_line) = text.lines.first_mut() {
+                if task.1.priority != Priority::None {
+                    first_line.spans.insert(
+                        0,
+                        Span::styled(
+                            format!("{} ", task.1.priority.marker()),
+                            Style::new().fg(priority_color(task.1.priority)),
+                        ),
+                    );
+                }
+                if selected {
+                    first_line.spans.insert(0, Span::raw("> "));
+                }
+            }
+            if !task.1.tags.is_empty() {
+                text.push_line(build_tags_line(&task.1.tags));
+            }
+            if let Some(recurrence) = &task.1.recurrence {
+                text.push_line(Line::styled(
+                    format!("↻ {}", recurrence),
+                    Style::new().cyan(),
+                ));
+            }
+            if task.1.is_blocked() {
+                let blockers: Vec<String> = task
+                    .1
+                    .blocked_by
+                    .iter()
+                    .map(|id| format!("#{id}"))
+                    .collect();
+                text.push_line(Line::styled(
+                    format!("⛔ Blocked by {}", blockers.join(", ")),
+                    Style::new().red(),
+                ));
+            }
+            if task.1.timer_started_at.is_some() || task.1.time_spent > Duration::zero() {
+                text.push_line(Line::styled(
+                    format!(
+                        "⏱ {}{}",
+                        format_duration(task.1.tracked_time(Local::now())),
+                        if task.1.timer_started_at.is_some() {
+                            " (running)"
+                        } else {
+                            ""
+                        }
+                    ),
+                    Style::new().magenta(),
+                ));
+            }
+            if task.1.checklist_total > 0 {
+                text.push_line(build_progress_line(
+                    task.1.checklist_done,
+                    task.1.checklist_total,
+                ));
+            }
+            text.extend(Text::from(fmt_data));
+
             if selected {
-                ListItem::new(fmt_data).style(Style::new().blue().italic())
+                ListItem::new(text).style(Style::new().blue().italic())
             } else {
-                ListItem::new(fmt_data).style(Style::new().green().bold())
+                ListItem::new(text).style(Style::new().green().bold())
             }
         })
         .collect();
+    let count = app.column_task_count(column);
+    let title = match column.wip_limit {
+        Some(limit) => format!("{} {}/{}", column.name, count, limit),
+        None => column.name.to_string(),
+    };
     List::new(tasks).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(if app.selected_status == *status {
+            .border_style(if column.is_over_limit(count) {
+                Style::new().red()
+            } else if is_current {
                 Style::new().blue()
             } else {
-                Style::new().green()
+                Style::new().fg(column_color(column))
             })
-            .title(status.get_message().unwrap()),
+            .title(title),
     )
 }
 
+fn column_color(column: &Column) -> Color {
+    Color::from_str(&column.color).unwrap_or(Color::Green)
+}
+
+pub fn render_columns(frame: &mut Frame, app: &AppState) {
+    let global_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)]);
+    let global_chunks = global_layout.split(frame.area());
+
+    let rect = Rect::new(
+        global_chunks[0].width / 4,
+        global_chunks[0].height / 4,
+        global_chunks[0].width / 2,
+        global_chunks[0].height / 2,
+    );
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(rect);
+
+    let items: Vec<ListItem> = app
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let selected = app.selected_column == i;
+            let line = Line::from(vec![
+                Span::raw(if selected { "> " } else { "" }),
+                Span::styled("■ ", Style::new().fg(column_color(column))),
+                Span::raw(format!(
+                    "{} ({}){}",
+                    column.name,
+                    column.status.get_message().unwrap(),
+                    column
+                        .wip_limit
+                        .map(|limit| format!(" WIP {limit}"))
+                        .unwrap_or_default()
+                )),
+            ]);
+            if selected {
+                ListItem::new(line).style(Style::new().blue().italic())
+            } else {
+                ListItem::new(line).style(Style::new().green().bold())
+            }
+        })
+        .collect();
+    let list = List::new(items).block(
+        Block::default()
+            .title(format!("Columns: {}", app.current_board_name()))
+            .borders(Borders::ALL)
+            .border_style(Style::new().green()),
+    );
+
+    frame.render_widget(list, layout[0]);
+    if let Some(message) = &app.status_message {
+        frame.render_widget(
+            Paragraph::new(message.to_string()).style(Style::new().red().bold()),
+            layout[1],
+        );
+    }
+    frame.render_widget(
+        build_controls_row(COLUMNS_CONTROLS.iter()),
+        global_chunks[1],
+    );
+}
+
+pub fn render_column_modal(frame: &mut Frame, state: &ColumnModalState, error: Option<&str>) {
+    render_line_modal(
+        frame,
+        if state.is_editing() {
+            "Rename Column"
+        } else {
+            "Create New Column"
+        },
+        "Enter column name...",
+        &state.name_in,
+        COLUMN_MODAL_CONTROLS.iter(),
+        error,
+    );
+}
+
+fn build_tags_line(tags: &[String]) -> Line<'static> {
+    let spans: Vec<Span> = tags
+        .iter()
+        .flat_map(|tag| {
+            [
+                Span::styled(
+                    format!(" #{} ", tag),
+                    Style::new().bg(tag_color(tag)).fg(Color::Black),
+                ),
+                Span::raw(" "),
+            ]
+        })
+        .collect();
+    Line::from(spans)
+}
+
+/// Draws checklist progress as a small text gauge, e.g. `[███░░░░░] 3/7`.
+fn build_progress_line(done: usize, total: usize) -> Line<'static> {
+    const WIDTH: usize = 8;
+    let filled = done * WIDTH / total;
+    Line::from(vec![
+        Span::styled("█".repeat(filled), Style::new().fg(Color::Green)),
+        Span::styled("░".repeat(WIDTH - filled), Style::new().fg(Color::DarkGray)),
+        Span::raw(format!(" {}/{}", done, total)),
+    ])
+}
+
+/// Picks a stable color for a tag so it looks the same on every card.
+fn tag_color(tag: &str) -> Color {
+    const PALETTE: [Color; 6] = [
+        Color::Cyan,
+        Color::Magenta,
+        Color::Yellow,
+        Color::LightRed,
+        Color::LightBlue,
+        Color::LightGreen,
+    ];
+    let hash = tag.bytes().fold(0usize, |acc, b| {
+        acc.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    PALETTE[hash % PALETTE.len()]
+}
+
+fn priority_color(priority: Priority) -> Color {
+    match priority {
+        Priority::None => Color::Reset,
+        Priority::Low => Color::Gray,
+        Priority::Medium => Color::Yellow,
+        Priority::High => Color::LightRed,
+        Priority::Urgent => Color::Red,
+    }
+}
+
+fn field_style(state: &TaskModalState, field: ModalField) -> Style {
+    if state.focus == field {
+        Style::new().white().bold()
+    } else {
+        Style::new().gray()
+    }
+}
+
 pub fn render_task_modal(frame: &mut Frame, state: &TaskModalState) {
     let global_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -102,7 +820,11 @@ pub fn render_task_modal(frame: &mut Frame, state: &TaskModalState) {
     let global_chunks = global_layout.split(frame.area());
 
     let block = Block::default()
-        .title("Create New Task")
+        .title(if state.is_editing() {
+            "Edit Task"
+        } else {
+            "Create New Task"
+        })
         .borders(Borders::ALL)
         .border_style(Style::new().green());
     let rect = Rect::new(
@@ -111,32 +833,184 @@ pub fn render_task_modal(frame: &mut Frame, state: &TaskModalState) {
         global_chunks[0].width / 2,
         global_chunks[0].height / 2,
     );
-    let text = Paragraph::new(if state.description_in.is_empty() {
-        "Enter task description...".to_string()
-    } else {
-        state.description_in.to_string()
-    })
-    .style(Style::new().white().bold())
-    .block(block);
+    let (deadline_preview, deadline_style) = match state.deadline() {
+        Ok(Some(deadline)) => (
+            Line::styled(
+                format!("→ {}", deadline.format("%a %d/%m/%Y %H:%M")),
+                Style::new().cyan(),
+            ),
+            field_style(state, ModalField::Deadline),
+        ),
+        Ok(None) => (Line::default(), field_style(state, ModalField::Deadline)),
+        Err(e) => (
+            Line::styled(format!("✗ {e}"), Style::new().red().bold()),
+            Style::new().red(),
+        ),
+    };
 
-    let deadline_text = Paragraph::new(if state.deadline_in.is_empty() {
-        "Enter deadline date (or leave empty)".to_string()
-    } else {
-        state.deadline_in.to_string()
-    })
-    .style(Style::new().white().bold());
+    let (recurrence_preview, recurrence_style) = match state.recurrence() {
+        Ok(Some(recurrence)) => (
+            Line::styled(format!("→ {recurrence}"), Style::new().cyan()),
+            field_style(state, ModalField::Recurrence),
+        ),
+        Ok(None) => (Line::default(), field_style(state, ModalField::Recurrence)),
+        Err(e) => (
+            Line::styled(format!("✗ {e}"), Style::new().red().bold()),
+            Style::new().red(),
+        ),
+    };
+
+    let priority_text = Paragraph::new(Line::from(vec![
+        Span::raw("Priority: "),
+        Span::styled(
+            state.priority.get_message().unwrap(),
+            Style::new().fg(priority_color(state.priority)),
+        ),
+    ]))
+    .style(Style::new().gray());
 
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage(80), // Backlog
-            Constraint::Percentage(20), // Today
+            Constraint::Percentage(60), // Description
+            Constraint::Percentage(10), // Deadline
+            Constraint::Percentage(10), // Tags
+            Constraint::Percentage(10), // Recurrence
+            Constraint::Percentage(10), // Priority
         ])
         .split(rect);
-    frame.render_widget(text, layout[0]);
-    frame.render_widget(deadline_text, layout[1]);
-    frame.render_widget(
-        build_controls_row(TASK_MODAL_CONTROLS.iter()),
-        global_chunks[1],
+    let deadline_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(layout[1]);
+    let recurrence_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(layout[3]);
+    // The terminal cursor only shows in the focused field, and not while the
+    // date picker covers the modal.
+    let focus = state.calendar.is_none().then_some(state.focus);
+
+    frame.render_widget(block.clone(), layout[0]);
+    render_text_input(
+        frame,
+        block.inner(layout[0]),
+        &state.description_in,
+        "Enter task description...",
+        field_style(state, ModalField::Description),
+        focus == Some(ModalField::Description),
     );
+    render_text_input(
+        frame,
+        deadline_layout[0],
+        &state.deadline_in,
+        "Enter deadline: 24/10/2026, tomorrow 17:00, next fri, in 3 days (or leave empty)",
+        deadline_style,
+        focus == Some(ModalField::Deadline),
+    );
+    frame.render_widget(Paragraph::new(deadline_preview), deadline_layout[1]);
+    render_text_input(
+        frame,
+        layout[2],
+        &state.tags_in,
+        "Enter tags separated by spaces (or leave empty)",
+        field_style(state, ModalField::Tags),
+        focus == Some(ModalField::Tags),
+    );
+    render_text_input(
+        frame,
+        recurrence_layout[0],
+        &state.recurrence_in,
+        "Repeat: daily / weekly mon,fri / monthly 15 / every 3 days (or leave empty)",
+        recurrence_style,
+        focus == Some(ModalField::Recurrence),
+    );
+    frame.render_widget(Paragraph::new(recurrence_preview), recurrence_layout[1]);
+    frame.render_widget(priority_text, layout[4]);
+    if let Some(cursor) = state.calendar {
+        render_calendar(frame, rect, cursor);
+        frame.render_widget(
+            build_controls_row(CALENDAR_CONTROLS.iter()),
+            global_chunks[1],
+        );
+        return;
+    }
+    let controls = if state.is_editing() {
+        EDIT_TASK_MODAL_CONTROLS.iter()
+    } else {
+        TASK_MODAL_CONTROLS.iter()
+    };
+    frame.render_widget(build_controls_row(controls), global_chunks[1]);
+}
+
+/// Draws `input` wrapped to the width of `area` and scrolled so the cursor
+/// stays in view; the terminal cursor is placed in it when `focused`.
+fn render_text_input(
+    frame: &mut Frame,
+    area: Rect,
+    input: &TextInput,
+    placeholder: &str,
+    style: Style,
+    focused: bool,
+) {
+    let (rows, (row, col)) = input.wrap(area.width as usize);
+    let scroll = (row as u16 + 1).saturating_sub(area.height);
+    let text = if input.is_empty() {
+        Text::from(placeholder)
+    } else {
+        Text::from(rows.into_iter().map(Line::from).collect::<Vec<_>>())
+    };
+    frame.render_widget(Paragraph::new(text).style(style).scroll((scroll, 0)), area);
+    if focused && area.height > 0 {
+        frame.set_cursor_position((area.x + col as u16, area.y + row as u16 - scroll));
+    }
+}
+
+/// Month grid around `cursor`, Monday first, drawn centered over `area`.
+fn render_calendar(frame: &mut Frame, area: Rect, cursor: NaiveDate) {
+    let width = 7 * 4 + 2;
+    let height = 6 + 4;
+    let rect = Rect::new(
+        area.x + area.width.saturating_sub(width) / 2,
+        area.y + area.height.saturating_sub(height) / 2,
+        width.min(area.width),
+        height.min(area.height),
+    );
+
+    let today = Local::now().date_naive();
+    let first = cursor.with_day(1).unwrap_or(cursor);
+    let grid_start = first - chrono::Days::new(first.weekday().num_days_from_monday() as u64);
+    let rows: Vec<Row> = (0..6)
+        .map(|week| {
+            Row::new((0..7).map(|day| {
+                let date = grid_start + chrono::Days::new(week * 7 + day);
+                let mut style = if date.month() == cursor.month() {
+                    Style::new().green()
+                } else {
+                    Style::new().dark_gray()
+                };
+                if date == today {
+                    style = style.underlined().bold();
+                }
+                if date == cursor {
+                    style = style.black().on_blue();
+                }
+                Cell::from(format!("{:>2}", date.day())).style(style)
+            }))
+        })
+        .collect();
+    let header =
+        Row::new(["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"]).style(Style::new().gray().bold());
+    let table = Table::new(rows, [Constraint::Length(3); 7])
+        .header(header)
+        .column_spacing(1)
+        .block(
+            Block::default()
+                .title(cursor.format("%B %Y").to_string())
+                .borders(Borders::ALL)
+                .border_style(Style::new().blue()),
+        );
+
+    frame.render_widget(Clear, rect);
+    frame.render_widget(table, rect);
 }
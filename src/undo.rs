@@ -0,0 +1,286 @@
+use chrono::Local;
+
+use crate::{
+    checklist::ChecklistItem,
+    column::Column,
+    config::WipMode,
+    db::Database,
+    error::AppError,
+    task::{Status, Task},
+};
+
+/// A reversible change to the tasks of a board.
+///
+/// Creating and deleting are mirror images: deleting moves the task to the
+/// trash and restoring brings it back under the same id.
+pub enum Command {
+    Create {
+        task: Task,
+        /// Checklist items copied into the task when it is first created.
+        checklist: Vec<String>,
+    },
+    Delete(Task),
+    Update {
+        before: Box<Task>,
+        after: Box<Task>,
+        /// Time entry started by moving the task into In Progress, deleted
+        /// again when the move is undone.
+        timer: Option<i64>,
+    },
+    /// Several commands applied in one transaction and undone and redone as
+    /// one, e.g. completing a recurring task together with spawning its next
+    /// occurrence.
+    Batch(Vec<Command>),
+}
+
+/// A checked move of a task to another column, see `Command::move_task`.
+pub struct Move {
+    pub command: Command,
+    /// Message for a move past a soft WIP limit.
+    pub wip_warning: Option<String>,
+}
+
+impl Move {
+    /// Next occurrence of the completed recurring task, with its id once the
+    /// command has run.
+    pub fn next_occurrence(&self) -> Option<&Task> {
+        match &self.command {
+            Command::Batch(commands) => commands.iter().find_map(|command| match command {
+                Command::Create { task, .. } => Some(task),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Command {
+    pub fn update(before: Task, after: Task) -> Command {
+        Command::Update {
+            before: Box::new(before),
+            after: Box::new(after),
+            timer: None,
+        }
+    }
+
+    /// Moves `task` to `column` the way the board and the CLI both do: blocked
+    /// tasks cannot start or finish, WIP limits apply and completing a
+    /// recurring task creates its next occurrence in the same batch.
+    pub fn move_task(
+        db: &Database,
+        task: &Task,
+        column: &Column,
+        wip_mode: WipMode,
+    ) -> Result<Move, AppError> {
+        let task_id = task.id.unwrap_or_default();
+        if task.is_blocked() && matches!(column.status, Status::InProgress | Status::Done) {
+            let blockers: Vec<String> = task.blocked_by.iter().map(|id| format!("#{id}")).collect();
+            return Err(AppError::Blocked {
+                task_id,
+                blockers: blockers.join(", "),
+            });
+        }
+        let mut after = task.clone();
+        let mut wip_warning = None;
+        if task.column_id != column.id {
+            let count = db
+                .load_tasks(task.board_id)?
+                .iter()
+                .filter(|t| t.column_id == column.id)
+                .count()
+                + 1;
+            if column.is_over_limit(count) {
+                let limit = column.wip_limit.unwrap_or_default();
+                if wip_mode == WipMode::Strict {
+                    return Err(AppError::WipLimit {
+                        column: column.name.to_string(),
+                        limit,
+                    });
+                }
+                wip_warning = Some(format!(
+                    "{} is over its WIP limit ({count}/{limit})",
+                    column.name
+                ));
+            }
+            after.position = None;
+        }
+        after.column_id = column.id;
+        after.status = column.status;
+
+        let mut commands = Vec::new();
+        if column.status == Status::Done
+            && task.status != Status::Done
+            && let Some(next) = after.next_occurrence(Local::now())
+        {
+            let checklist = db
+                .load_checklist(task_id)?
+                .into_iter()
+                .map(|item| item.description)
+                .collect();
+            commands.push(Command::Create {
+                task: next,
+                checklist,
+            });
+            // The rule moves on to the new occurrence, so re-completing this one
+            // does not spawn a duplicate.
+            after.recurrence = None;
+        }
+        commands.insert(0, Command::update(task.clone(), after));
+        let command = match commands.len() {
+            1 => commands.remove(0),
+            _ => Command::Batch(commands),
+        };
+        Ok(Move {
+            command,
+            wip_warning,
+        })
+    }
+
+    pub fn apply(&mut self, db: &mut Database) -> Result<(), AppError> {
+        match self {
+            Command::Create { task, checklist } => match task.id {
+                Some(id) => db.restore_task(id)?,
+                None => {
+                    let id = db.insert_task(task)?;
+                    task.id = Some(id);
+                    for description in checklist.iter() {
+                        db.insert_checklist_item(&ChecklistItem::new(id, description.to_string()))?;
+                    }
+                }
+            },
+            Command::Delete(task) => db.delete_task(task)?,
+            Command::Update {
+                before,
+                after,
+                timer,
+            } => {
+                db.update_task(after)?;
+                *timer = None;
+                if let Some(id) = after.id {
+                    match (
+                        before.status == Status::InProgress,
+                        after.status == Status::InProgress,
+                    ) {
+                        (false, true) => *timer = db.start_timer(id)?,
+                        (true, false) => db.stop_timer(id)?,
+                        _ => {}
+                    }
+                }
+            }
+            Command::Batch(commands) => db.transaction(|db| {
+                commands
+                    .iter_mut()
+                    .try_for_each(|command| command.apply(db))
+            })?,
+        }
+        Ok(())
+    }
+
+    pub fn revert(&mut self, db: &mut Database) -> Result<(), AppError> {
+        match self {
+            Command::Create { task, .. } => db.delete_task(task)?,
+            Command::Delete(task) => {
+                if let Some(id) = task.id {
+                    db.restore_task(id)?;
+                }
+            }
+            Command::Update {
+                before,
+                after,
+                timer,
+            } => {
+                db.update_task_at(before, before.updated_at)?;
+                if let Some(id) = before.id {
+                    match (
+                        after.status == Status::InProgress,
+                        before.status == Status::InProgress,
+                    ) {
+                        (true, false) => match timer.take() {
+                            Some(entry_id) => db.delete_time_entry(entry_id)?,
+                            None => db.stop_timer(id)?,
+                        },
+                        (false, true) => {
+                            db.start_timer(id)?;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Command::Batch(commands) => db.transaction(|db| {
+                commands
+                    .iter_mut()
+                    .rev()
+                    .try_for_each(|command| command.revert(db))
+            })?,
+        }
+        Ok(())
+    }
+
+    /// Task the command is about, selected on the board after undo or redo.
+    pub fn task_id(&self) -> Option<i64> {
+        match self {
+            Command::Create { task, .. } | Command::Delete(task) => task.id,
+            Command::Update { after, .. } => after.id,
+            Command::Batch(commands) => commands.first().and_then(Command::task_id),
+        }
+    }
+
+    /// Short name of the command for the status message.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Command::Create { .. } => "create",
+            Command::Delete(_) => "delete",
+            Command::Update { before, after, .. } => {
+                if before.board_id != after.board_id || before.column_id != after.column_id {
+                    "move"
+                } else if before.position != after.position {
+                    "reorder"
+                } else {
+                    "edit"
+                }
+            }
+            Command::Batch(commands) => commands.first().map_or("change", Command::title),
+        }
+    }
+}
+
+/// Commands done and undone during the session.
+#[derive(Default)]
+pub struct UndoStack {
+    done: Vec<Command>,
+    undone: Vec<Command>,
+}
+
+impl UndoStack {
+    /// Applies a new command; it clears whatever could be redone.
+    pub fn execute(&mut self, db: &mut Database, mut command: Command) -> Result<(), AppError> {
+        command.apply(db)?;
+        self.done.push(command);
+        self.undone.clear();
+        Ok(())
+    }
+
+    pub fn undo(&mut self, db: &mut Database) -> Result<Option<&Command>, AppError> {
+        let Some(mut command) = self.done.pop() else {
+            return Ok(None);
+        };
+        if let Err(e) = command.revert(db) {
+            self.done.push(command);
+            return Err(e);
+        }
+        self.undone.push(command);
+        Ok(self.undone.last())
+    }
+
+    pub fn redo(&mut self, db: &mut Database) -> Result<Option<&Command>, AppError> {
+        let Some(mut command) = self.undone.pop() else {
+            return Ok(None);
+        };
+        if let Err(e) = command.apply(db) {
+            self.undone.push(command);
+            return Err(e);
+        }
+        self.done.push(command);
+        Ok(self.done.last())
+    }
+}
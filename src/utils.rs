@@ -1,28 +1,120 @@
-use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use std::str::FromStr;
 
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+
+/// Parses a deadline typed by the user, see `parse_deadline`.
 pub fn str_to_local_dt(s: &str) -> Option<DateTime<Local>> {
-    // Try full datetime first
-    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%d/%m/%Y %H:%M") {
-        let res = Local.from_local_datetime(&dt);
-        return match res {
-            chrono::offset::LocalResult::Single(t) => Some(t),
-            chrono::offset::LocalResult::Ambiguous(t1, _) => Some(t1),
-            chrono::offset::LocalResult::None => None,
-        };
+    parse_deadline(s, Local::now())
+}
+
+/// Parses absolute dates (`24/10/2026`, `2026-10-24`, `24/10`) and relative
+/// ones (`today`, `tomorrow`, `yesterday`, `fri`, `next fri`, `in 3 days`, `next week`,
+/// `eow`, `eom`), optionally followed by a time of day such as `17:00`,
+/// `at 17:00` or `5pm`. A bare weekday is its next occurrence, today included;
+/// `next fri` is the Friday of next week. Dates without a time fall on midnight.
+pub fn parse_deadline(s: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let s = s.trim().to_lowercase();
+    for fmt in ["%d/%m/%Y %H:%M", "%Y-%m-%d %H:%M", "%Y-%m-%dt%H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(&s, fmt) {
+            return local_dt(dt);
+        }
     }
 
-    // Try only date
-    if let Ok(date) = NaiveDate::parse_from_str(s, "%d/%m/%Y") {
-        let dt = date.and_time(NaiveTime::from_hms_opt(0, 0, 0)?);
-        let res = Local.from_local_datetime(&dt);
-        return match res {
-            chrono::offset::LocalResult::Single(t) => Some(t),
-            chrono::offset::LocalResult::Ambiguous(t1, _) => Some(t1),
-            chrono::offset::LocalResult::None => None,
-        };
+    let words: Vec<&str> = s.split_whitespace().filter(|w| *w != "at").collect();
+    let (words, time) = match words
+        .split_last()
+        .map(|(last, rest)| (rest, parse_time(last)))
+    {
+        Some((rest, Some(time))) => (rest, Some(time)),
+        _ => (words.as_slice(), None),
+    };
+
+    let today = now.date_naive();
+    let date = match words {
+        [] if time.is_some() => today,
+        ["today"] => today,
+        ["tomorrow"] | ["tmr"] => today.succ_opt()?,
+        ["yesterday"] => today.pred_opt()?,
+        ["eow"] => today.checked_add_days(chrono::Days::new(
+            6 - today.weekday().num_days_from_monday() as u64,
+        ))?,
+        ["eom"] => today
+            .with_day(1)?
+            .checked_add_months(chrono::Months::new(1))?
+            .pred_opt()?,
+        ["in", n, unit] => {
+            let n: u32 = n.parse().ok()?;
+            match *unit {
+                "minute" | "minutes" | "min" | "mins" => {
+                    return now.checked_add_signed(chrono::Duration::try_minutes(n as i64)?);
+                }
+                "hour" | "hours" | "h" => {
+                    return now.checked_add_signed(chrono::Duration::try_hours(n as i64)?);
+                }
+                "day" | "days" | "d" => today.checked_add_days(chrono::Days::new(n as u64))?,
+                "week" | "weeks" | "w" => {
+                    today.checked_add_days(chrono::Days::new(7 * n as u64))?
+                }
+                "month" | "months" => today.checked_add_months(chrono::Months::new(n))?,
+                _ => return None,
+            }
+        }
+        ["next", "week"] => week_start(today).checked_add_days(chrono::Days::new(7))?,
+        ["next", "month"] => today
+            .with_day(1)?
+            .checked_add_months(chrono::Months::new(1))?,
+        ["next", day] => {
+            let weekday = Weekday::from_str(day).ok()?;
+            week_start(today)
+                .checked_add_days(chrono::Days::new(7 + weekday.num_days_from_monday() as u64))?
+        }
+        ["this", day] => upcoming_weekday(today, Weekday::from_str(day).ok()?)?,
+        [word] => match Weekday::from_str(word) {
+            Ok(weekday) => upcoming_weekday(today, weekday)?,
+            Err(_) => parse_date(word, today)?,
+        },
+        _ => return None,
+    };
+    local_dt(date.and_time(time.unwrap_or(NaiveTime::MIN)))
+}
+
+/// `24/10/2026`, `2026-10-24` or `24/10` (in the current year).
+fn parse_date(s: &str, today: NaiveDate) -> Option<NaiveDate> {
+    ["%d/%m/%Y", "%Y-%m-%d"]
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(s, fmt).ok())
+        .or_else(|| NaiveDate::parse_from_str(&format!("{s}/{}", today.year()), "%d/%m/%Y").ok())
+}
+
+/// `17:00`, `5pm` or `5:30am`.
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    if let Ok(time) = NaiveTime::parse_from_str(s, "%H:%M") {
+        return Some(time);
+    }
+    let (clock, pm) = match (s.strip_suffix("am"), s.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, false),
+        (_, Some(clock)) => (clock, true),
+        _ => return None,
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+    if !(1..=12).contains(&hour) {
+        return None;
     }
+    NaiveTime::from_hms_opt(hour % 12 + if pm { 12 } else { 0 }, minute, 0)
+}
 
-    None
+/// The first `weekday` from `date` on, `date` itself included.
+fn upcoming_weekday(date: NaiveDate, weekday: Weekday) -> Option<NaiveDate> {
+    let days_ahead =
+        (weekday.num_days_from_monday() + 7 - date.weekday().num_days_from_monday()) % 7;
+    date.checked_add_days(chrono::Days::new(days_ahead as u64))
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - chrono::Days::new(date.weekday().num_days_from_monday() as u64)
 }
 
 pub fn db_timestamp_to_local_dt(dt_str: &str) -> DateTime<Local> {
@@ -33,3 +125,151 @@ pub fn db_timestamp_to_local_dt(dt_str: &str) -> DateTime<Local> {
             chrono::Local.from_local_datetime(&naive).unwrap()
         })
 }
+
+/// `dt` moved by `days`, or `None` past the dates chrono can represent.
+pub fn add_days(dt: DateTime<Local>, days: i64) -> Option<DateTime<Local>> {
+    dt.checked_add_signed(chrono::Duration::try_days(days)?)
+}
+
+/// First date strictly after `dt` that falls on one of `weekdays`, keeping the time of day.
+pub fn next_weekday_after(dt: DateTime<Local>, weekdays: &[Weekday]) -> Option<DateTime<Local>> {
+    if weekdays.is_empty() {
+        return add_days(dt, 7);
+    }
+    (1..=7)
+        .filter_map(|i| add_days(dt, i))
+        .find(|d| weekdays.contains(&d.weekday()))
+}
+
+/// First day `day` of a month strictly after `dt`, keeping the time of day.
+/// The day is clamped to the month length, so day 31 is the 30th in April.
+pub fn next_month_day_after(dt: DateTime<Local>, day: u32) -> Option<DateTime<Local>> {
+    let this_month = dt.date_naive().with_day(1)?;
+    [
+        Some(this_month),
+        this_month.checked_add_months(chrono::Months::new(1)),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|first| {
+        let days_in_month = first
+            .checked_add_months(chrono::Months::new(1))?
+            .pred_opt()?
+            .day();
+        let date = first.with_day(day.clamp(1, days_in_month))?;
+        local_dt(date.and_time(dt.time()))
+    })
+    .find(|candidate| *candidate > dt)
+}
+
+/// Short form of a tracked duration such as `2h 05m` or `12m`.
+pub fn format_duration(d: chrono::Duration) -> String {
+    let minutes = d.num_minutes().max(0);
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn local_dt(dt: NaiveDateTime) -> Option<DateTime<Local>> {
+    match Local.from_local_datetime(&dt) {
+        chrono::offset::LocalResult::Single(t) => Some(t),
+        chrono::offset::LocalResult::Ambiguous(t1, _) => Some(t1),
+        chrono::offset::LocalResult::None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday 2026-10-14, 10:30.
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 14, 10, 30, 0).unwrap()
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> Option<DateTime<Local>> {
+        Local.with_ymd_and_hms(y, m, d, h, min, 0).single()
+    }
+
+    #[test]
+    fn parses_absolute_dates() {
+        for s in ["24/10/2026", "2026-10-24", "24/10", " 2026-10-24 "] {
+            assert_eq!(parse_deadline(s, now()), at(2026, 10, 24, 0, 0), "{s}");
+        }
+        assert_eq!(
+            parse_deadline("24/10/2026 17:15", now()),
+            at(2026, 10, 24, 17, 15)
+        );
+        assert_eq!(
+            parse_deadline("2026-10-24T17:15", now()),
+            at(2026, 10, 24, 17, 15)
+        );
+        assert_eq!(
+            parse_deadline("24/10 at 5pm", now()),
+            at(2026, 10, 24, 17, 0)
+        );
+    }
+
+    #[test]
+    fn parses_relative_dates() {
+        let cases = [
+            ("today", at(2026, 10, 14, 0, 0)),
+            ("Tomorrow 9am", at(2026, 10, 15, 9, 0)),
+            ("yesterday", at(2026, 10, 13, 0, 0)),
+            ("17:00", at(2026, 10, 14, 17, 0)),
+            ("wed", at(2026, 10, 14, 0, 0)),
+            ("fri", at(2026, 10, 16, 0, 0)),
+            ("this mon", at(2026, 10, 19, 0, 0)),
+            ("next fri", at(2026, 10, 23, 0, 0)),
+            ("next week", at(2026, 10, 19, 0, 0)),
+            ("next month", at(2026, 11, 1, 0, 0)),
+            ("eow", at(2026, 10, 18, 0, 0)),
+            ("eom", at(2026, 10, 31, 0, 0)),
+            ("in 3 days", at(2026, 10, 17, 0, 0)),
+            ("in 2 weeks", at(2026, 10, 28, 0, 0)),
+            ("in 4 months", at(2027, 2, 14, 0, 0)),
+            ("in 90 min", at(2026, 10, 14, 12, 0)),
+            ("in 2 hours", at(2026, 10, 14, 12, 30)),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(parse_deadline(s, now()), expected, "{s}");
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_input() {
+        for s in [
+            "",
+            "soon",
+            "in 3 fortnights",
+            "in -1 days",
+            "32/10/2026",
+            "13pm",
+            "next",
+        ] {
+            assert_eq!(parse_deadline(s, now()), None, "{s}");
+        }
+    }
+
+    #[test]
+    fn out_of_range_relative_dates_are_none() {
+        for s in [
+            "in 99999999 days",
+            "in 4000000000 weeks",
+            "in 4000000000 months",
+            "in 4000000000 hours",
+        ] {
+            assert_eq!(parse_deadline(s, now()), None, "{s}");
+        }
+        assert!(parse_deadline("in 36500 days", now()).is_some());
+    }
+
+    #[test]
+    fn next_month_day_clamps_to_month_length() {
+        let dt = Local.with_ymd_and_hms(2026, 3, 31, 9, 0, 0).unwrap();
+        assert_eq!(next_month_day_after(dt, 31), at(2026, 4, 30, 9, 0));
+        assert_eq!(add_days(dt, i64::MAX), None);
+    }
+}